@@ -1,7 +1,13 @@
+use std::path::PathBuf;
 use std::time::Duration;
 
-use crate::format::parse_duration;
-use clap::{Parser, Subcommand};
+use chrono::NaiveDate;
+
+use clap::{ArgAction, Parser, Subcommand};
+use porsmo::export::{parse_date, parse_format, ExportFormat};
+use porsmo::format::{parse_duration, parse_named_timer};
+use porsmo::interval::{parse_plan, Plan};
+use porsmo::routine::{parse_repeat, Repeat};
 
 #[derive(Parser)]
 #[command(author, version, about)]
@@ -29,6 +35,29 @@ pub enum CounterMode {
         /// target time: example values 30m 20m 40m 2h25m30s
         #[arg(value_parser = parse_duration, value_name = "time")]
         target: Duration,
+        /// start over after every alert, a number of times or forever
+        #[arg(long, value_parser = parse_repeat, value_name = "N|forever")]
+        repeat: Option<Repeat>,
+    },
+    /// alias: m, timers, several named timers at once, more can be added with [A]
+    #[command(name = "timers", alias = "m")]
+    Timers {
+        /// timers to start with: example values tea=4m "deploy check=15m" 30s
+        #[arg(value_parser = parse_named_timer, value_name = "name=time")]
+        timers: Vec<(Option<String>, Duration)>,
+    },
+    /// alias: i, interval, rounds of work and rest, for HIIT and Tabata
+    #[command(name = "interval", alias = "i")]
+    Interval {
+        /// rounds, work and rest time: example values 8x20s/10s 5x1m/30s
+        #[arg(value_parser = parse_plan, value_name = "rounds>x<work>/<rest")]
+        plan: Plan,
+        /// warm up before the first round: example values 2m 5m
+        #[arg(long, value_parser = parse_duration, value_name = "time")]
+        warmup: Option<Duration>,
+        /// cool down after the last round: example values 2m 5m
+        #[arg(long, value_parser = parse_duration, value_name = "time")]
+        cooldown: Option<Duration>,
     },
     /// alias: p, pomodoro, for all you productivity needs (default)
     #[command(name = "pomodoro", alias = "p")]
@@ -38,6 +67,64 @@ pub enum CounterMode {
         ///Display a message after quitting the pomodoro timer
         #[arg(short, name = "exitmessage")]
         exitmessage: bool,
+        /// template for the exit message, placeholders: {worked} {rested}
+        /// {overtime} {extended} {sessions} {breaks} {skips} {pauses} {paused}
+        /// {interruptions}
+        #[arg(long, value_name = "template", requires = "exitmessage")]
+        exit_template: Option<String>,
+        /// label the work sessions with what they are for, can be changed with [T]
+        #[arg(short, long, value_name = "label")]
+        task: Option<String>,
+        /// start breaks automatically once work ends, =false turns it off
+        #[arg(long, value_name = "bool", num_args = 0..=1, require_equals = true, default_missing_value = "true", action = ArgAction::Set)]
+        auto_start_breaks: Option<bool>,
+        /// start long breaks automatically, follows --auto-start-breaks by default
+        #[arg(long, value_name = "bool", num_args = 0..=1, require_equals = true, default_missing_value = "true", action = ArgAction::Set)]
+        auto_start_long_breaks: Option<bool>,
+        /// start working automatically once a break ends, =false turns it off
+        #[arg(long, value_name = "bool", num_args = 0..=1, require_equals = true, default_missing_value = "true", action = ArgAction::Set)]
+        auto_start_work: Option<bool>,
+        /// grace period before a phase is started automatically: example values 10s 1m
+        #[arg(long, value_parser = parse_duration, value_name = "time")]
+        auto_start_delay: Option<Duration>,
+    },
+    /// alias: r, routine, runs a phase sequence defined under [routines] in the config
+    #[command(name = "routine", alias = "r")]
+    Routine {
+        /// name of the routine in the config
+        name: String,
+    },
+    /// show the focus time recorded in the history, grouped by task
+    #[command(name = "stats")]
+    Stats,
+    /// write the recorded history as csv, ics or json
+    #[command(name = "export")]
+    Export {
+        /// csv for timesheets, ics for calendars, json for everything else
+        #[arg(short, long, value_parser = parse_format, default_value = "csv", value_name = "csv|ics|json")]
+        format: ExportFormat,
+        /// only phases started on or after this day: example value 2024-03-01
+        #[arg(long, value_parser = parse_date, value_name = "date")]
+        from: Option<NaiveDate>,
+        /// only phases started on or before this day: example value 2024-03-31
+        #[arg(long, value_parser = parse_date, value_name = "date")]
+        to: Option<NaiveDate>,
+        /// write to a file instead of the standard output
+        #[arg(short, long, value_name = "file")]
+        output: Option<PathBuf>,
+    },
+    /// merge a history file from another machine into this one
+    #[command(name = "import")]
+    Import {
+        /// a history.jsonl, or the output of export --format json
+        #[arg(value_name = "file")]
+        file: PathBuf,
+        /// machine the entries came from, required if some entries don't say
+        #[arg(long, value_name = "name")]
+        host: Option<String>,
+        /// also import entries that overlap ones recorded on another machine
+        #[arg(long)]
+        keep_overlaps: bool,
     },
 }
 
@@ -1,28 +1,105 @@
+use std::cell::Cell;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone, Copy)]
+use chrono::{DateTime, Local, TimeDelta};
+
+/// Where a [`Clock`] reads the time: the system's by default, or a manual
+/// time that stands still until it's moved, to replay scripted input
+/// without waiting for it. Clones share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct TimeSource {
+    manual: Option<Rc<ManualTime>>,
+}
+
+#[derive(Debug)]
+struct ManualTime {
+    instant: Instant,
+    local: DateTime<Local>,
+    offset: Cell<Duration>,
+}
+
+impl TimeSource {
+    /// Manual time, starting at `local`.
+    pub fn manual(local: DateTime<Local>) -> Self {
+        Self {
+            manual: Some(Rc::new(ManualTime {
+                instant: Instant::now(),
+                local,
+                offset: Cell::new(Duration::ZERO),
+            })),
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        match &self.manual {
+            Some(manual) => manual.instant + manual.offset.get(),
+            None => Instant::now(),
+        }
+    }
+
+    /// The date and time, for records.
+    pub fn local(&self) -> DateTime<Local> {
+        match &self.manual {
+            Some(manual) => {
+                let offset = TimeDelta::from_std(manual.offset.get()).unwrap_or(TimeDelta::MAX);
+                manual.local + offset
+            }
+            None => Local::now(),
+        }
+    }
+
+    /// Moves manual time forward.
+    ///
+    /// # Panics
+    ///
+    /// The system time can't be moved.
+    pub fn advance(&self, by: Duration) {
+        let manual = self.manual.as_ref().expect("Only manual time can be moved");
+        manual.offset.set(manual.offset.get() + by);
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Clock {
+    time: TimeSource,
     start_time: Option<Instant>,
     accumulated: Duration,
 }
 
 impl Default for Clock {
     fn default() -> Self {
-        Self {
-            start_time: Some(Instant::now()),
-            accumulated: Duration::ZERO,
-        }
+        Self::new(TimeSource::default())
     }
 }
 
 impl Clock {
+    /// A clock running from now on `time`.
+    pub fn new(time: TimeSource) -> Self {
+        Self::resumed(time, Duration::ZERO, true)
+    }
+
+    /// A clock that already counted `elapsed`, running on from now if
+    /// `running`.
+    pub fn resumed(time: TimeSource, elapsed: Duration, running: bool) -> Self {
+        Self {
+            start_time: running.then(|| time.now()),
+            accumulated: elapsed,
+            time,
+        }
+    }
+
+    pub fn time(&self) -> &TimeSource {
+        &self.time
+    }
+
     pub fn is_running(&self) -> bool {
         self.start_time.is_some()
     }
 
     pub fn elapsed(&self) -> Duration {
         match self.start_time {
-            Some(start_time) => start_time.elapsed() + self.accumulated,
+            Some(start_time) => self.time.now() - start_time + self.accumulated,
             None => self.accumulated,
         }
     }
@@ -30,10 +107,10 @@ impl Clock {
     pub fn toggle(&mut self) {
         match self.start_time {
             Some(start_time) => {
-                self.accumulated += start_time.elapsed();
+                self.accumulated += self.time.now() - start_time;
                 self.start_time = None;
             }
-            None => self.start_time = Some(Instant::now()),
+            None => self.start_time = Some(self.time.now()),
         }
     }
 
@@ -50,8 +127,42 @@ impl Clock {
     //     }
     // }
 
+    /// Takes `amount` off the elapsed time.
+    pub fn discard(&mut self, amount: Duration) {
+        let kept = self.elapsed().saturating_sub(amount);
+        self.accumulated = kept;
+        if self.start_time.is_some() {
+            self.start_time = Some(self.time.now());
+        }
+    }
+
     pub fn reset(&mut self) {
-        self.start_time = Some(Instant::now());
+        self.start_time = Some(self.time.now());
         self.accumulated = Duration::ZERO;
     }
 }
+
+/// Time added to a countdown after it started. Extending one whose time is
+/// already up first moves its target to now, that time stays overtime
+/// rather than counting as extended.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Extension {
+    /// What was asked for with the extend keys.
+    pub added: Duration,
+    /// Time that was already up when it was extended.
+    pub overtime: Duration,
+}
+
+impl Extension {
+    /// Adds `amount` on top of whatever is left of `planned` after
+    /// `elapsed`, or on top of `elapsed` if nothing is left.
+    pub fn add(&mut self, planned: Duration, elapsed: Duration, amount: Duration) {
+        self.overtime += elapsed.saturating_sub(self.target(planned));
+        self.added += amount;
+    }
+
+    /// When a countdown of `planned` with this extension is up.
+    pub fn target(&self, planned: Duration) -> Duration {
+        planned + self.added + self.overtime
+    }
+}
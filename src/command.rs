@@ -0,0 +1,65 @@
+//! Commands typed into the pomodoro's command palette.
+
+use std::time::Duration;
+
+use crate::format::parse_duration;
+use crate::pomodoro::Mode;
+use crate::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// `extend 5m`
+    Extend(Duration),
+    /// `task fix flaky test`, or just `task` to clear it.
+    Task(Option<String>),
+    /// `set break 7m` changes the current phase too if it's a break, `set 7m`
+    /// changes the current kind of phase.
+    Set(Option<Mode>, Duration),
+    /// `goto long-break`
+    Goto(Mode),
+    /// `back` to the previous phase.
+    Back,
+}
+
+/// Usage of every command, for the help screen.
+pub const USAGE: [(&str, &str); 5] = [
+    ("extend <time>", "Add time to the current phase"),
+    ("task [text]", "Set or clear the task"),
+    ("set [phase] <time>", "Change how long phases last"),
+    ("goto <phase>", "Skip to a phase: work, break or long-break"),
+    ("back", "Go back to the previous phase"),
+];
+
+pub fn parse_mode(text: &str) -> Result<Mode> {
+    match text {
+        "work" => Ok(Mode::Work),
+        "break" => Ok(Mode::Break),
+        "long-break" | "long_break" => Ok(Mode::LongBreak),
+        _ => Err(anyhow!(
+            "Unknown phase {text:?}, expected work, break or long-break"
+        )),
+    }
+}
+
+pub fn parse_command(text: &str) -> Result<Command> {
+    let text = text.trim();
+    let (name, args) = text.split_once(' ').unwrap_or((text, ""));
+    let args = args.trim();
+    let words: Vec<&str> = args.split_whitespace().collect();
+    match (name, words.as_slice()) {
+        ("extend", [time]) => Ok(Command::Extend(parse_duration(time)?)),
+        ("task", _) => Ok(Command::Task((!args.is_empty()).then(|| args.to_string()))),
+        ("set", [time]) => Ok(Command::Set(None, parse_duration(time)?)),
+        ("set", [mode, time]) => Ok(Command::Set(Some(parse_mode(mode)?), parse_duration(time)?)),
+        ("goto", [mode]) => Ok(Command::Goto(parse_mode(mode)?)),
+        ("back", []) => Ok(Command::Back),
+        ("extend" | "set" | "goto" | "back", _) => {
+            let (usage, _) = USAGE
+                .iter()
+                .find(|(usage, _)| usage.starts_with(name))
+                .expect("every command has a usage");
+            Err(anyhow!("Usage: {usage}"))
+        }
+        _ => Err(anyhow!("Unknown command {name:?}")),
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::goal::GoalConfig;
+use crate::hooks::HooksConfig;
+use crate::idle::IdleConfig;
+use crate::prelude::*;
+use crate::routine::RoutineConfig;
+use crate::warning::WarningConfig;
+
+/// Settings read from `config.toml`, every field is optional.
+///
+/// ```toml
+/// [extend]
+/// short = "1m"
+/// long = "5m"
+///
+/// [auto_start]
+/// breaks = true
+/// long_breaks = false
+/// work = true
+/// delay = "10s"
+///
+/// [confirm]
+/// reset = true
+///
+/// [pause]
+/// max = "10m"
+///
+/// [warnings]
+/// at = ["5m", "1m"]
+/// countdown = "10s"
+///
+/// [idle]
+/// after = "5m"
+///
+/// [goal]
+/// pomodoros = 8
+///
+/// [terminal]
+/// title = true
+/// progress = false
+/// mouse = true
+///
+/// [hooks]
+/// warning = 'notify-send porsmo "$PORSMO_LABEL ends in $PORSMO_REMAINING"'
+/// goal = 'notify-send porsmo "Daily goal reached"'
+///
+/// [routines.ultradian]
+/// repeat = 2
+/// phases = [
+///     { name = "plan", duration = "5m", color = "yellow", auto_advance = true },
+///     { name = "deep work", duration = "90m", color = "red" },
+///     { name = "walk", duration = "15m", color = "green", sound = "low" },
+/// ]
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub extend: ExtendConfig,
+    pub auto_start: AutoStartConfig,
+    pub confirm: ConfirmConfig,
+    pub pause: PauseConfig,
+    /// Named phase sequences, run with `porsmo routine <name>`.
+    pub routines: BTreeMap<String, RoutineConfig>,
+    pub warnings: WarningConfig,
+    pub idle: IdleConfig,
+    pub goal: GoalConfig,
+    pub terminal: TerminalConfig,
+    pub hooks: HooksConfig,
+}
+
+/// How much time the extend keys add to the current phase.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExtendConfig {
+    #[serde(with = "duration")]
+    pub short: Duration,
+    #[serde(with = "duration")]
+    pub long: Duration,
+}
+
+impl Default for ExtendConfig {
+    fn default() -> Self {
+        Self {
+            short: Duration::from_secs(60),
+            long: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+/// Which pomodoro phases start by themselves once the previous one ended,
+/// `delay` is the grace period after the alert.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutoStartConfig {
+    pub breaks: bool,
+    /// Follows `breaks` unless set.
+    pub long_breaks: Option<bool>,
+    pub work: bool,
+    #[serde(with = "duration")]
+    pub delay: Duration,
+}
+
+/// Keys that ask before doing something that's hard to take back. Skipping
+/// always asks.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConfirmConfig {
+    pub reset: bool,
+}
+
+/// A reminder once a pomodoro was paused for `max`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PauseConfig {
+    #[serde(with = "duration::optional")]
+    pub max: Option<Duration>,
+}
+
+/// What porsmo shows outside of its own screen.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TerminalConfig {
+    /// Show the countdown in the window title.
+    pub title: bool,
+    /// Report progress with OSC 9;4, which some terminals show in the
+    /// taskbar.
+    pub progress: bool,
+    /// Capture the mouse so the controls can be clicked. Off by default as
+    /// it gets in the way of selecting text in most terminals.
+    pub mouse: bool,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self {
+            title: true,
+            progress: false,
+            mouse: false,
+        }
+    }
+}
+
+/// Location of the config file, `$PORSMO_CONFIG` takes precedence over the
+/// platform config directory.
+pub fn config_path() -> Result<PathBuf> {
+    if let Some(path) = std::env::var_os("PORSMO_CONFIG") {
+        return Ok(PathBuf::from(path));
+    }
+    let dir = dirs::config_dir().context("Could not find a config directory")?;
+    Ok(dir.join("porsmo").join("config.toml"))
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        let path = config_path()?;
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let config: Self = toml::from_str(&text)
+            .with_context(|| format!("Invalid config in {}", path.display()))?;
+        config
+            .idle
+            .validate()
+            .with_context(|| format!("Invalid config in {}", path.display()))?;
+        Ok(config)
+    }
+}
+
+/// Durations are written the same way as on the command line, e.g. `"1h30m"`.
+pub(crate) mod duration {
+    use serde::{de::Error, Deserialize, Deserializer};
+    use std::time::Duration;
+
+    use crate::format::parse_duration;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse_duration(&text).map_err(D::Error::custom)
+    }
+
+    pub mod optional {
+        use super::*;
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<Duration>, D::Error> {
+            super::deserialize(deserializer).map(Some)
+        }
+    }
+}
+
+/// A list of durations, e.g. `["5m", "1m"]`.
+pub(crate) mod durations {
+    use serde::{de::Error, Deserialize, Deserializer};
+    use std::time::Duration;
+
+    use crate::format::parse_duration;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Duration>, D::Error> {
+        let texts = Vec::<String>::deserialize(deserializer)?;
+        texts
+            .iter()
+            .map(|text| parse_duration(text).map_err(D::Error::custom))
+            .collect()
+    }
+}
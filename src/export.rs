@@ -0,0 +1,186 @@
+//! Turning the history into files other tools can read.
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, TimeZone, Utc};
+
+use crate::format::format_duration_short;
+use crate::history::PhaseRecord;
+use crate::pomodoro::Mode;
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One row per phase, for timesheets and spreadsheets.
+    Csv,
+    /// One event per work phase, for calendar clients.
+    Ics,
+    /// The records as stored in the history.
+    Json,
+}
+
+pub fn parse_format(text: &str) -> Result<ExportFormat> {
+    match text {
+        "csv" => Ok(ExportFormat::Csv),
+        "ics" => Ok(ExportFormat::Ics),
+        "json" => Ok(ExportFormat::Json),
+        _ => Err(anyhow!(
+            "Unknown format {text:?}, expected csv, ics or json"
+        )),
+    }
+}
+
+/// Parses a `YYYY-MM-DD` date.
+pub fn parse_date(text: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .with_context(|| format!("Expected a date like 2024-03-31, got {text:?}"))
+}
+
+/// Keeps the records that started between `from` and `to`, both inclusive.
+pub fn between(
+    records: Vec<PhaseRecord>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Vec<PhaseRecord> {
+    records
+        .into_iter()
+        .filter(|record| {
+            let date = record.start.date_naive();
+            from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to)
+        })
+        .collect()
+}
+
+pub fn export(records: &[PhaseRecord], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Csv => Ok(to_csv(records)),
+        ExportFormat::Ics => Ok(to_ics(records, Utc::now())),
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(records)? + "\n"),
+    }
+}
+
+fn mode_name(mode: Mode) -> &'static str {
+    match mode {
+        Mode::Work => "work",
+        Mode::Break => "break",
+        Mode::LongBreak => "long_break",
+    }
+}
+
+const CSV_HEADER: &str = "id,date,start,end,mode,session,task,planned_seconds,\
+    elapsed_seconds,elapsed,extended_seconds,skipped,pauses,paused_seconds,interruptions,host";
+
+pub fn to_csv(records: &[PhaseRecord]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push_str("\r\n");
+    for record in records {
+        let fields = [
+            record.id.to_string(),
+            record.start.format("%Y-%m-%d").to_string(),
+            // Full timestamps, a phase can end on the next day.
+            record.start.to_rfc3339_opts(SecondsFormat::Secs, false),
+            record.end.to_rfc3339_opts(SecondsFormat::Secs, false),
+            mode_name(record.mode).to_string(),
+            record.session.to_string(),
+            record.task.clone().unwrap_or_default(),
+            record.planned.as_secs().to_string(),
+            record.elapsed.as_secs().to_string(),
+            format_duration_short(record.elapsed),
+            record.extended.as_secs().to_string(),
+            record.skipped.to_string(),
+            record.pauses.to_string(),
+            record.paused.as_secs().to_string(),
+            record.interruptions.len().to_string(),
+            record.host.clone().unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Every work phase becomes a VEVENT named after its task.
+pub fn to_ics(records: &[PhaseRecord], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//porsmo//porsmo//EN".to_string(),
+    ];
+    for record in records.iter().filter(|record| record.mode == Mode::Work) {
+        let mut description = format!(
+            "Session {}, {} of {}",
+            record.session,
+            format_duration_short(record.elapsed),
+            format_duration_short(record.planned),
+        );
+        if record.skipped {
+            description += ", skipped";
+        }
+        let count = |n: usize, what: &str| match n {
+            1 => format!(", 1 {what}"),
+            n => format!(", {n} {what}s"),
+        };
+        if record.pauses > 0 {
+            description += &count(record.pauses as usize, "pause");
+        }
+        if !record.interruptions.is_empty() {
+            description += &count(record.interruptions.len(), "interruption");
+        }
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@porsmo", record.id),
+            format!("DTSTAMP:{}", ics_time(now)),
+            format!("DTSTART:{}", ics_time(record.start)),
+            format!("DTEND:{}", ics_time(record.end)),
+            format!(
+                "SUMMARY:{}",
+                ics_text(record.task.as_deref().unwrap_or("Focus"))
+            ),
+            format!("DESCRIPTION:{}", ics_text(&description)),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = String::new();
+    for line in lines {
+        ics.push_str(&fold(&line));
+        ics.push_str("\r\n");
+    }
+    ics
+}
+
+fn ics_time<Tz: TimeZone>(time: DateTime<Tz>) -> String {
+    time.with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Lines longer than 75 bytes continue on the next line after a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
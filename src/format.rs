@@ -29,6 +29,17 @@ pub fn format_duration_short(dur: Duration) -> String {
     }
 }
 
+/// Digital clock style, e.g. `04:59` or `1:02:03`.
+pub fn format_clock(dur: Duration) -> String {
+    let total_secs = dur.as_secs();
+    let (hours, mins, secs) = (total_secs / 3600, total_secs / 60 % 60, total_secs % 60);
+    if hours == 0 {
+        format!("{mins:02}:{secs:02}")
+    } else {
+        format!("{hours}:{mins:02}:{secs:02}")
+    }
+}
+
 pub fn parse_duration(text: &str) -> Result<Duration> {
     let (hours, text) = match text.split_once('h') {
         Some((hours, rest)) => {
@@ -57,3 +68,15 @@ pub fn parse_duration(text: &str) -> Result<Duration> {
 
     Ok(hours + mins + secs)
 }
+
+/// Parses `name=time`, a bare `time` is fine too.
+pub fn parse_named_timer(text: &str) -> Result<(Option<String>, Duration)> {
+    match text.rsplit_once('=') {
+        Some((name, time)) => {
+            let name = name.trim();
+            let name = (!name.is_empty()).then(|| name.to_string());
+            Ok((name, parse_duration(time.trim())?))
+        }
+        None => Ok((None, parse_duration(text.trim())?)),
+    }
+}
@@ -0,0 +1,141 @@
+//! Daily focus goals, configured under `[goal]`.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{Days, Local, NaiveDate};
+use serde::Deserialize;
+
+use crate::config::duration;
+use crate::format::format_duration_short;
+use crate::history::PhaseRecord;
+use crate::pomodoro::Mode;
+
+/// Either target, or both, can be set.
+///
+/// ```toml
+/// [goal]
+/// pomodoros = 8
+/// focus = "4h"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GoalConfig {
+    pub pomodoros: Option<u32>,
+    #[serde(with = "duration::optional")]
+    pub focus: Option<Duration>,
+}
+
+/// Work done on one day. Skipped work counts towards focus time, but not
+/// as a pomodoro.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Progress {
+    pub pomodoros: u32,
+    pub focus: Duration,
+}
+
+impl Progress {
+    pub fn add(&mut self, record: &PhaseRecord) {
+        if record.mode != Mode::Work {
+            return;
+        }
+        self.focus += record.elapsed;
+        if !record.skipped {
+            self.pomodoros += 1;
+        }
+    }
+}
+
+/// Work done on `date`, from all runs on any machine.
+pub fn progress_on(records: &[PhaseRecord], date: NaiveDate) -> Progress {
+    let mut progress = Progress::default();
+    for record in records.iter().filter(|r| r.start.date_naive() == date) {
+        progress.add(record);
+    }
+    progress
+}
+
+pub fn today(records: &[PhaseRecord]) -> Progress {
+    progress_on(records, Local::now().date_naive())
+}
+
+impl GoalConfig {
+    pub fn is_set(&self) -> bool {
+        self.pomodoros.is_some() || self.focus.is_some()
+    }
+
+    /// Every target that is set was reached.
+    pub fn is_met(&self, progress: &Progress) -> bool {
+        self.is_set()
+            && self.pomodoros.is_none_or(|goal| progress.pomodoros >= goal)
+            && self.focus.is_none_or(|goal| progress.focus >= goal)
+    }
+
+    /// E.g. `Today: 5/8 🍅, 2h 5m 0s/4h 0m 0s focus`, `None` without a goal.
+    pub fn describe(&self, progress: &Progress) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(goal) = self.pomodoros {
+            parts.push(format!("{}/{goal} 🍅", progress.pomodoros));
+        }
+        if let Some(goal) = self.focus {
+            parts.push(format!(
+                "{}/{} focus",
+                format_duration_short(progress.focus),
+                format_duration_short(goal),
+            ));
+        }
+        if parts.is_empty() {
+            return None;
+        }
+        let done = if self.is_met(progress) {
+            ", goal reached!"
+        } else {
+            ""
+        };
+        Some(format!("Today: {}{done}", parts.join(", ")))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Streaks {
+    /// Days in a row the goal was met, up to today. Today only counts once
+    /// it's met, so the streak isn't broken until the day is over.
+    pub current: u32,
+    pub best: u32,
+}
+
+pub fn streaks(records: &[PhaseRecord], goal: &GoalConfig, today: NaiveDate) -> Streaks {
+    let mut days: BTreeMap<NaiveDate, Progress> = BTreeMap::new();
+    for record in records {
+        days.entry(record.start.date_naive())
+            .or_default()
+            .add(record);
+    }
+    let met = |date: NaiveDate| days.get(&date).is_some_and(|p| goal.is_met(p));
+
+    let mut best = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for (&date, progress) in &days {
+        if !goal.is_met(progress) {
+            run = 0;
+        } else if previous.and_then(|p| p.checked_add_days(Days::new(1))) == Some(date) && run > 0 {
+            run += 1;
+        } else {
+            run = 1;
+        }
+        best = best.max(run);
+        previous = Some(date);
+    }
+
+    let mut current = 0;
+    let mut date = match met(today) {
+        true => today,
+        false => today - Days::new(1),
+    };
+    while met(date) {
+        current += 1;
+        date = date - Days::new(1);
+    }
+    Streaks { current, best }
+}
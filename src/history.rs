@@ -0,0 +1,273 @@
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::pomodoro::{Event, Mode, Observer};
+use crate::prelude::*;
+
+/// What happened during a single work or break phase.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseRecord {
+    pub id: Uuid,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub mode: Mode,
+    pub session: u32,
+    #[serde(with = "secs")]
+    pub planned: Duration,
+    /// Time added on top of `planned` with the extend keys.
+    #[serde(with = "secs", default)]
+    pub extended: Duration,
+    #[serde(with = "secs")]
+    pub elapsed: Duration,
+    pub skipped: bool,
+    pub pauses: u32,
+    /// Total length of the pauses.
+    #[serde(with = "secs", default)]
+    pub paused: Duration,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interruptions: Vec<Interruption>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    /// The machine the phase was recorded on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+}
+
+impl PhaseRecord {
+    pub fn overtime(&self) -> Duration {
+        self.elapsed.saturating_sub(self.planned + self.extended)
+    }
+
+    pub fn overlaps(&self, other: &PhaseRecord) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterruptionKind {
+    Internal,
+    External,
+}
+
+impl fmt::Display for InterruptionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Internal => write!(f, "Internal"),
+            Self::External => write!(f, "External"),
+        }
+    }
+}
+
+/// An interruption logged during a work phase, `at` is the phase's elapsed
+/// time when it happened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interruption {
+    pub kind: InterruptionKind,
+    #[serde(with = "secs")]
+    pub at: Duration,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Location of the history file, `$PORSMO_HISTORY` takes precedence over
+/// the platform data directory.
+pub fn history_path() -> Result<PathBuf> {
+    if let Some(path) = std::env::var_os("PORSMO_HISTORY") {
+        return Ok(PathBuf::from(path));
+    }
+    let dir = dirs::data_dir().context("Could not find a data directory for the history")?;
+    Ok(dir.join("porsmo").join("history.jsonl"))
+}
+
+pub fn append(record: &PhaseRecord) -> Result<()> {
+    append_to(&history_path()?, record)
+}
+
+pub fn append_to(path: &Path, record: &PhaseRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let line = serde_json::to_string(record)?;
+    writeln!(file, "{line}").with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Takes the record with `id` back out of a history file.
+pub fn remove_from(path: &Path, id: Uuid) -> Result<()> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let mut kept = String::new();
+    for line in text.lines() {
+        let record: Option<PhaseRecord> = serde_json::from_str(line).ok();
+        if record.is_some_and(|record| record.id == id) {
+            continue;
+        }
+        kept.push_str(line);
+        kept.push('\n');
+    }
+    // Replaced in one go so a crash can't leave half a history behind.
+    let temp = path.with_extension("jsonl.tmp");
+    fs::write(&temp, kept).with_context(|| format!("Failed to write {}", temp.display()))?;
+    fs::rename(&temp, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+/// Name of this machine, stored with every record.
+pub fn hostname() -> Option<String> {
+    static HOSTNAME: OnceLock<Option<String>> = OnceLock::new();
+    HOSTNAME
+        .get_or_init(|| {
+            let name = std::env::var("HOSTNAME")
+                .ok()
+                .or_else(|| fs::read_to_string("/etc/hostname").ok())
+                .or_else(|| {
+                    let output = Command::new("hostname").output().ok()?;
+                    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+                })?;
+            let name = name.trim();
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .clone()
+}
+
+pub fn load() -> Result<Vec<PhaseRecord>> {
+    load_from(&history_path()?)
+}
+
+/// Reads a history file, a missing file is an empty history.
+pub fn load_from(path: &Path) -> Result<Vec<PhaseRecord>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to open {}", path.display())),
+    };
+
+    let mut records = Vec::new();
+    for (n, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .with_context(|| format!("Malformed entry at {}:{}", path.display(), n + 1))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Like [`load_from`], but leaves out lines that aren't records, e.g. one
+/// cut short by a crash, and says how many there were.
+pub fn load_readable(path: &Path) -> Result<(Vec<PhaseRecord>, usize)> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((Vec::new(), 0)),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    let mut records = Vec::new();
+    let mut skipped = 0;
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str(line) {
+            Ok(record) => records.push(record),
+            Err(_) => skipped += 1,
+        }
+    }
+    Ok((records, skipped))
+}
+
+/// Appends every phase to the history file at `path` as soon as it ends,
+/// and takes it out again if it's picked up again.
+///
+/// A failed write doesn't stop the pomodoro, the change is kept and tried
+/// again with the next one or on [`HistoryWriter::flush`].
+pub struct HistoryWriter {
+    path: PathBuf,
+    pending: Vec<Change>,
+    error: Option<String>,
+}
+
+enum Change {
+    Append(PhaseRecord),
+    Remove(Uuid),
+}
+
+impl HistoryWriter {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            pending: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Writes every change that couldn't be written yet.
+    pub fn flush(&mut self) -> Result<()> {
+        while let Some(change) = self.pending.first() {
+            match change {
+                Change::Append(record) => append_to(&self.path, record)?,
+                Change::Remove(id) => remove_from(&self.path, *id)?,
+            }
+            self.pending.remove(0);
+        }
+        Ok(())
+    }
+
+    /// Why the last write failed, once.
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
+
+impl Observer for HistoryWriter {
+    fn on_event(&mut self, event: &Event) -> Result<()> {
+        match event {
+            Event::PhaseEnded(record) => self.pending.push(Change::Append(record.clone())),
+            Event::PhaseReopened(record) => {
+                let unwritten = self.pending.iter().position(
+                    |change| matches!(change, Change::Append(pending) if pending.id == record.id),
+                );
+                match unwritten {
+                    Some(i) => {
+                        self.pending.remove(i);
+                    }
+                    None => self.pending.push(Change::Remove(record.id)),
+                }
+            }
+            _ => return Ok(()),
+        }
+        if let Err(e) = self.flush() {
+            self.error = Some(format!("History not saved: {e:#}"));
+        }
+        Ok(())
+    }
+}
+
+mod secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(dur: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(dur.as_secs())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(Duration::from_secs(u64::deserialize(deserializer)?))
+    }
+}
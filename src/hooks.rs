@@ -0,0 +1,42 @@
+//! User commands run on events, configured under `[hooks]`.
+
+use std::process::{Command, Stdio};
+
+use serde::Deserialize;
+
+use crate::prelude::*;
+
+/// Every hook is a shell command, details are passed in `PORSMO_*`
+/// environment variables.
+///
+/// ```toml
+/// [hooks]
+/// warning = 'notify-send porsmo "$PORSMO_LABEL ends in $PORSMO_REMAINING"'
+/// goal = 'notify-send porsmo "Goal reached: $PORSMO_POMODOROS pomodoros"'
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Runs when a warning fires, with `PORSMO_LABEL` and `PORSMO_REMAINING`.
+    pub warning: Option<String>,
+    /// Runs when the daily goal is reached, with `PORSMO_POMODOROS` and
+    /// `PORSMO_FOCUS`.
+    pub goal: Option<String>,
+}
+
+/// Starts `command` with `sh -c` without waiting for it, its output is
+/// discarded so it can't mess up the terminal.
+pub fn run(command: &str, event: &str, env: &[(&str, String)]) -> Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("PORSMO_EVENT", event)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run the {event} hook"))?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
@@ -0,0 +1,106 @@
+//! Finding out how long the user has been away, configured under `[idle]`.
+
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+use crate::config::duration;
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleSource {
+    /// Key presses and focus changes in porsmo's own terminal. Work isn't
+    /// paused while nothing happens, time spent elsewhere might be work too,
+    /// the next key press asks whether to keep the quiet time instead.
+    #[default]
+    Terminal,
+    /// The session's `IdleHint`, as reported by `loginctl`.
+    Logind,
+    /// The output of `command`, in milliseconds, e.g. `xprintidle`.
+    Command,
+}
+
+/// Work is paused once nothing happened for `after`, or with the terminal
+/// source, the user is asked about it when they're back. Idle detection is
+/// off unless it's set.
+///
+/// ```toml
+/// [idle]
+/// after = "5m"
+/// source = "command"
+/// command = "xprintidle"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdleConfig {
+    #[serde(with = "duration::optional")]
+    pub after: Option<Duration>,
+    pub source: IdleSource,
+    pub command: Option<String>,
+}
+
+impl IdleConfig {
+    /// Catches a source that can't work before it's needed.
+    pub fn validate(&self) -> Result<()> {
+        if self.source == IdleSource::Command && self.command.is_none() {
+            return Err(anyhow!("The idle command source needs a command"));
+        }
+        Ok(())
+    }
+}
+
+/// Asks the system how long the user has been idle. The terminal source is
+/// tracked by the front end, it's always zero here.
+pub fn system_idle(config: &IdleConfig) -> Result<Duration> {
+    match config.source {
+        IdleSource::Terminal => Ok(Duration::ZERO),
+        IdleSource::Logind => logind_idle(),
+        IdleSource::Command => {
+            let command = config
+                .command
+                .as_deref()
+                .context("The idle command source needs a command")?;
+            let output = run(Command::new("sh").arg("-c").arg(command))?;
+            let millis = output
+                .trim()
+                .parse::<u64>()
+                .with_context(|| format!("Expected milliseconds from {command:?}"))?;
+            Ok(Duration::from_millis(millis))
+        }
+    }
+}
+
+fn logind_idle() -> Result<Duration> {
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string());
+    let output = run(Command::new("loginctl").args([
+        "show-session",
+        &session,
+        "--property=IdleHint",
+        "--property=IdleSinceHint",
+    ]))?;
+    let property = |name: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix('='))
+    };
+    if property("IdleHint") != Some("yes") {
+        return Ok(Duration::ZERO);
+    }
+    let since = property("IdleSinceHint")
+        .context("loginctl didn't report IdleSinceHint")?
+        .parse::<u64>()?;
+    let since = UNIX_EPOCH + Duration::from_micros(since);
+    Ok(SystemTime::now().duration_since(since).unwrap_or_default())
+}
+
+fn run(command: &mut Command) -> Result<String> {
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to run {command:?}"))?;
+    if !output.status.success() {
+        return Err(anyhow!("{command:?} failed with {}", output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,66 @@
+//! Merging history recorded on other machines into this one.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::history::{self, PhaseRecord};
+use crate::prelude::*;
+
+/// What [`merge`] made of the incoming records.
+#[derive(Debug, Default)]
+pub struct Merge {
+    /// Records to add to the history.
+    pub added: Vec<PhaseRecord>,
+    /// Records that were already in the history.
+    pub duplicates: usize,
+    /// Records left out because they overlap one from another machine.
+    pub overlapping: usize,
+}
+
+/// Reads either a history file or the output of `porsmo export -f json`.
+pub fn read(path: &Path) -> Result<Vec<PhaseRecord>> {
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if text.trim_start().starts_with('[') {
+        return serde_json::from_str(&text)
+            .with_context(|| format!("Malformed history in {}", path.display()));
+    }
+    history::load_from(path)
+}
+
+/// Picks the incoming records that are new. Records without a host are
+/// attributed to `host`, it's an error if there's none. A record whose time
+/// overlaps one recorded on another machine is left out unless
+/// `keep_overlaps` is set, as the same time can't be spent twice.
+pub fn merge(
+    existing: &[PhaseRecord],
+    incoming: Vec<PhaseRecord>,
+    host: Option<&str>,
+    keep_overlaps: bool,
+) -> Result<Merge> {
+    let mut ids: HashSet<_> = existing.iter().map(|record| record.id).collect();
+    let mut merge = Merge::default();
+    for mut record in incoming {
+        if !ids.insert(record.id) {
+            merge.duplicates += 1;
+            continue;
+        }
+        if record.host.is_none() {
+            let host = host.context(
+                "Some entries don't say which machine they were recorded on, name it with --host",
+            )?;
+            record.host = Some(host.to_string());
+        }
+        let overlaps = existing
+            .iter()
+            .chain(&merge.added)
+            .any(|other| other.host != record.host && other.overlaps(&record));
+        if overlaps && !keep_overlaps {
+            merge.overlapping += 1;
+            continue;
+        }
+        merge.added.push(record);
+    }
+    Ok(merge)
+}
@@ -0,0 +1,195 @@
+//! Interval training: rounds of work and rest with an optional warm-up and
+//! cool-down, all timed by a single [`Clock`].
+
+use std::time::Duration;
+
+use crate::clock::{Clock, TimeSource};
+use crate::format::parse_duration;
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Warmup,
+    Work,
+    Rest,
+    Cooldown,
+}
+
+/// One stretch of the plan, rounds are counted from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub phase: Phase,
+    pub round: u32,
+    pub duration: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Plan {
+    pub rounds: u32,
+    pub work: Duration,
+    pub rest: Duration,
+    pub warmup: Duration,
+    pub cooldown: Duration,
+}
+
+/// Parses `<rounds>x<work>/<rest>`, e.g. `8x20s/10s`.
+pub fn parse_plan(text: &str) -> Result<Plan> {
+    let (rounds, times) = text
+        .split_once('x')
+        .ok_or_else(|| anyhow!("Expected <rounds>x<work>/<rest>, e.g. 8x20s/10s"))?;
+    let (work, rest) = times
+        .split_once('/')
+        .ok_or_else(|| anyhow!("Expected <rounds>x<work>/<rest>, e.g. 8x20s/10s"))?;
+    let rounds = rounds.parse::<u32>()?;
+    if rounds == 0 {
+        return Err(anyhow!("There should be at least one round."));
+    }
+    Ok(Plan {
+        rounds,
+        work: parse_duration(work)?,
+        rest: parse_duration(rest)?,
+        warmup: Duration::ZERO,
+        cooldown: Duration::ZERO,
+    })
+}
+
+impl Plan {
+    /// Every step in order, there's no rest after the last round.
+    pub fn steps(&self) -> Vec<Step> {
+        let step = |phase, round, duration| Step {
+            phase,
+            round,
+            duration,
+        };
+        let mut steps = Vec::new();
+        if !self.warmup.is_zero() {
+            steps.push(step(Phase::Warmup, 0, self.warmup));
+        }
+        for round in 1..=self.rounds {
+            steps.push(step(Phase::Work, round, self.work));
+            if round < self.rounds && !self.rest.is_zero() {
+                steps.push(step(Phase::Rest, round, self.rest));
+            }
+        }
+        if !self.cooldown.is_zero() {
+            steps.push(step(Phase::Cooldown, self.rounds, self.cooldown));
+        }
+        steps
+    }
+}
+
+/// A sound the front end should play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    /// A work interval started.
+    Start,
+    /// A work interval ended.
+    End,
+    /// One of the last three seconds of a step.
+    Countdown,
+    /// The whole plan is done.
+    Finished,
+}
+
+pub struct Interval {
+    plan: Plan,
+    steps: Vec<Step>,
+    clock: Clock,
+    cued_step: Option<usize>,
+    cued_second: Option<u128>,
+    finished: bool,
+}
+
+impl Interval {
+    pub fn new(plan: Plan) -> Self {
+        Self {
+            plan,
+            steps: plan.steps(),
+            clock: Clock::default(),
+            cued_step: None,
+            cued_second: None,
+            finished: false,
+        }
+    }
+
+    /// Reads the time from `time` instead of the system.
+    pub fn with_time(mut self, time: TimeSource) -> Self {
+        self.clock = Clock::new(time);
+        self
+    }
+
+    pub fn plan(&self) -> &Plan {
+        &self.plan
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.clock.is_running()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    pub fn total(&self) -> Duration {
+        self.steps.iter().map(|step| step.duration).sum()
+    }
+
+    /// The current step and how much of it is left, `None` once done.
+    pub fn current(&self) -> Option<(usize, Step, Duration)> {
+        let mut elapsed = self.clock.elapsed();
+        for (index, step) in self.steps.iter().enumerate() {
+            if elapsed < step.duration {
+                return Some((index, *step, step.duration - elapsed));
+            }
+            elapsed -= step.duration;
+        }
+        None
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.current().is_none()
+    }
+
+    /// Checks the clock, to be called regularly. Returns the cues that
+    /// became due since the last call, each fires once.
+    pub fn tick(&mut self) -> Vec<Cue> {
+        let mut cues = Vec::new();
+        let Some((index, step, remaining)) = self.current() else {
+            if !self.finished {
+                self.finished = true;
+                cues.push(Cue::Finished);
+            }
+            return cues;
+        };
+
+        if self.cued_step != Some(index) {
+            self.cued_step = Some(index);
+            self.cued_second = None;
+            if index > 0 && self.steps[index - 1].phase == Phase::Work {
+                cues.push(Cue::End);
+            }
+            if step.phase == Phase::Work {
+                cues.push(Cue::Start);
+            }
+        }
+
+        let second = remaining.as_millis().div_ceil(1000);
+        if (1..=3).contains(&second) && self.cued_second != Some(second) {
+            self.cued_second = Some(second);
+            cues.push(Cue::Countdown);
+        }
+        cues
+    }
+
+    pub fn toggle(&mut self) {
+        self.clock.toggle();
+    }
+
+    /// Starts the whole plan over.
+    pub fn reset(&mut self) {
+        self.clock.reset();
+        self.cued_step = None;
+        self.cued_second = None;
+        self.finished = false;
+    }
+}
@@ -0,0 +1,30 @@
+//! Pomodoro, timer and stopwatch logic behind the `porsmo` command.
+//!
+//! The core ([`clock`], [`format`], [`pomodoro`], [`command`], [`interval`],
+//! [`routine`], [`warning`], [`idle`], [`goal`], [`history`], [`summary`],
+//! [`export`], [`import`], [`config`] and [`hooks`]) has no terminal or
+//! audio dependencies. The terminal front end lives in [`tui`] behind the
+//! `tui` feature.
+
+pub mod clock;
+pub mod command;
+pub mod config;
+pub mod export;
+pub mod format;
+pub mod goal;
+pub mod history;
+pub mod hooks;
+pub mod idle;
+pub mod import;
+pub mod interval;
+pub mod pomodoro;
+pub mod prelude;
+pub mod routine;
+pub mod summary;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod warning;
+
+pub use clock::Clock;
+pub use format::{format_duration, format_duration_short, parse_duration};
+pub use pomodoro::{Durations, Event, Mode, Observer, Pomodoro};
@@ -0,0 +1,561 @@
+//! The pomodoro state machine, free of any terminal or audio handling.
+//!
+//! ```no_run
+//! use porsmo::pomodoro::{Durations, Event, Pomodoro};
+//!
+//! let mut pomodoro = Pomodoro::new(Durations::SHORT);
+//! pomodoro.subscribe(|event: &Event| {
+//!     if let Event::TimeUp { mode, .. } = event {
+//!         println!("{mode:?} is over");
+//!     }
+//!     Ok(())
+//! });
+//! loop {
+//!     pomodoro.tick()?;
+//!     std::thread::sleep(std::time::Duration::from_millis(250));
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::clock::{Clock, Extension, TimeSource};
+use crate::config::AutoStartConfig;
+use crate::history::{self, Interruption, PhaseRecord};
+use crate::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    Work,
+    Break,
+    LongBreak,
+}
+
+/// Planned length of each kind of phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Durations {
+    pub work: Duration,
+    pub short_break: Duration,
+    pub long_break: Duration,
+}
+
+impl Durations {
+    pub const SHORT: Self = Self {
+        work: Duration::from_secs(25 * 60),
+        short_break: Duration::from_secs(5 * 60),
+        long_break: Duration::from_secs(10 * 60),
+    };
+
+    pub const LONG: Self = Self {
+        work: Duration::from_secs(50 * 60),
+        short_break: Duration::from_secs(10 * 60),
+        long_break: Duration::from_secs(20 * 60),
+    };
+
+    pub fn of(&self, mode: Mode) -> Duration {
+        match mode {
+            Mode::Work => self.work,
+            Mode::Break => self.short_break,
+            Mode::LongBreak => self.long_break,
+        }
+    }
+}
+
+/// Something that happened to a [`Pomodoro`], delivered to its observers.
+#[derive(Debug, Clone)]
+pub enum Event {
+    PhaseStarted {
+        mode: Mode,
+        session: u32,
+    },
+    /// The planned (and extended) time of the phase ran out.
+    TimeUp {
+        mode: Mode,
+        session: u32,
+    },
+    PhaseEnded(PhaseRecord),
+    /// A phase that ended was picked up again, its record no longer counts.
+    PhaseReopened(PhaseRecord),
+    Paused,
+    Resumed,
+    Extended(Duration),
+    /// `mode` phases last `duration` from now on.
+    DurationChanged {
+        mode: Mode,
+        duration: Duration,
+    },
+    Interrupted(Interruption),
+    TaskChanged(Option<String>),
+}
+
+pub trait Observer {
+    fn on_event(&mut self, event: &Event) -> Result<()>;
+}
+
+impl<F: FnMut(&Event) -> Result<()>> Observer for F {
+    fn on_event(&mut self, event: &Event) -> Result<()> {
+        self(event)
+    }
+}
+
+/// What [`Pomodoro::undo`] took back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Undone {
+    Reset,
+    /// A skip, a jump or the start of the next phase.
+    PhaseChange,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Undo {
+    Reset {
+        elapsed: Duration,
+        extension: Extension,
+        time_up: bool,
+        reopened: bool,
+    },
+    PhaseChange,
+}
+
+/// What a finished phase's record doesn't say, to pick it up again.
+#[derive(Debug, Clone, Copy)]
+struct Ended {
+    phase: usize,
+    running: bool,
+    /// The record only has the extension that was asked for.
+    extension: Extension,
+    time_up: bool,
+    long_break_at: u32,
+}
+
+pub struct Pomodoro {
+    durations: Durations,
+    auto_start: AutoStartConfig,
+    clock: Clock,
+    mode: Mode,
+    session: u32,
+    /// Sequence number of the current phase, kept by [`Pomodoro::back`].
+    phase: usize,
+    /// How many phases were started.
+    phases: usize,
+    time_up: bool,
+    /// Set on a phase that was gone back to, it doesn't start the next one
+    /// by itself until it's extended, reset or left.
+    reopened: bool,
+    extension: Extension,
+    pauses: u32,
+    /// Length of the finished pauses of this phase.
+    paused: Duration,
+    /// When the current pause started.
+    paused_at: Option<Instant>,
+    interruptions: Vec<Interruption>,
+    task: Option<String>,
+    started_at: DateTime<Local>,
+    records: Vec<PhaseRecord>,
+    /// One entry per record, for going back to it.
+    ended: Vec<Ended>,
+    /// The session followed by the next long break.
+    long_break_at: u32,
+    undo: Vec<Undo>,
+    observers: Vec<Box<dyn Observer>>,
+}
+
+impl Pomodoro {
+    pub fn new(durations: Durations) -> Self {
+        Self {
+            durations,
+            auto_start: AutoStartConfig::default(),
+            clock: Clock::default(),
+            mode: Mode::Work,
+            session: 1,
+            phase: 1,
+            phases: 1,
+            time_up: false,
+            reopened: false,
+            extension: Extension::default(),
+            pauses: 0,
+            paused: Duration::ZERO,
+            paused_at: None,
+            interruptions: Vec::new(),
+            task: None,
+            started_at: Local::now(),
+            records: Vec::new(),
+            ended: Vec::new(),
+            long_break_at: 4,
+            undo: Vec::new(),
+            observers: Vec::new(),
+        }
+    }
+
+    pub fn with_auto_start(mut self, auto_start: AutoStartConfig) -> Self {
+        self.auto_start = auto_start;
+        self
+    }
+
+    pub fn with_task(mut self, task: Option<String>) -> Self {
+        self.task = task;
+        self
+    }
+
+    /// Reads the time from `time` instead of the system.
+    pub fn with_time(mut self, time: TimeSource) -> Self {
+        self.started_at = time.local();
+        self.clock = Clock::new(time);
+        self
+    }
+
+    pub fn time(&self) -> &TimeSource {
+        self.clock.time()
+    }
+
+    pub fn subscribe(&mut self, observer: impl Observer + 'static) {
+        self.observers.push(Box::new(observer));
+    }
+
+    fn emit(&mut self, event: Event) -> Result<()> {
+        for observer in &mut self.observers {
+            observer.on_event(&event)?;
+        }
+        Ok(())
+    }
+
+    pub fn durations(&self) -> Durations {
+        self.durations
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn session(&self) -> u32 {
+        self.session
+    }
+
+    /// Numbers the phases from 1 as they start, going back to a phase
+    /// brings its number back too.
+    pub fn phase(&self) -> usize {
+        self.phase
+    }
+
+    pub fn task(&self) -> Option<&str> {
+        self.task.as_deref()
+    }
+
+    pub fn interruptions(&self) -> &[Interruption] {
+        &self.interruptions
+    }
+
+    /// Phases finished so far, the current one is not included.
+    pub fn records(&self) -> &[PhaseRecord] {
+        &self.records
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.clock.is_running()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    /// Planned time of the current phase plus its extensions.
+    pub fn target(&self) -> Duration {
+        self.extension.target(self.durations.of(self.mode))
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.target().saturating_sub(self.elapsed())
+    }
+
+    pub fn overtime(&self) -> Duration {
+        self.elapsed().saturating_sub(self.target())
+    }
+
+    pub fn is_time_up(&self) -> bool {
+        self.elapsed() >= self.target()
+    }
+
+    /// Every fourth work session is followed by a long break, counting from
+    /// the last long break, even one taken early.
+    pub fn next_mode(&self) -> Mode {
+        match self.mode {
+            Mode::Work if self.session >= self.long_break_at => Mode::LongBreak,
+            Mode::Work => Mode::Break,
+            Mode::Break | Mode::LongBreak => Mode::Work,
+        }
+    }
+
+    pub fn auto_starts(&self, mode: Mode) -> bool {
+        match mode {
+            Mode::Work => self.auto_start.work,
+            Mode::Break => self.auto_start.breaks,
+            Mode::LongBreak => self
+                .auto_start
+                .long_breaks
+                .unwrap_or(self.auto_start.breaks),
+        }
+    }
+
+    /// Time until the next phase starts by itself, if it does.
+    pub fn auto_start_in(&self) -> Option<Duration> {
+        if self.reopened || !self.is_time_up() || !self.auto_starts(self.next_mode()) {
+            return None;
+        }
+        Some((self.target() + self.auto_start.delay).saturating_sub(self.elapsed()))
+    }
+
+    /// Checks the clock, to be called regularly. Fires [`Event::TimeUp`] once
+    /// per phase and starts the next phase when it's set to auto start.
+    pub fn tick(&mut self) -> Result<()> {
+        if !self.is_time_up() {
+            return Ok(());
+        }
+        if !self.time_up {
+            self.time_up = true;
+            self.emit(Event::TimeUp {
+                mode: self.mode,
+                session: self.session,
+            })?;
+        }
+        if self.auto_start_in() == Some(Duration::ZERO) {
+            self.advance()?;
+        }
+        Ok(())
+    }
+
+    pub fn toggle(&mut self) -> Result<()> {
+        if self.clock.is_running() {
+            self.pauses += 1;
+            self.paused_at = Some(self.time().now());
+            self.clock.toggle();
+            self.emit(Event::Paused)
+        } else {
+            self.paused += self.current_pause().unwrap_or_default();
+            self.paused_at = None;
+            self.clock.toggle();
+            self.emit(Event::Resumed)
+        }
+    }
+
+    /// How often this phase was paused.
+    pub fn pauses(&self) -> u32 {
+        self.pauses
+    }
+
+    /// How long this phase was paused, including the current pause.
+    pub fn paused(&self) -> Duration {
+        self.paused + self.current_pause().unwrap_or_default()
+    }
+
+    /// How long the pause going on right now has lasted.
+    pub fn current_pause(&self) -> Option<Duration> {
+        self.paused_at.map(|at| self.time().now() - at)
+    }
+
+    /// Restarts the current phase from zero, extensions are dropped. A
+    /// pause ends with it, as the clock runs again.
+    pub fn reset(&mut self) {
+        self.undo.push(Undo::Reset {
+            elapsed: self.clock.elapsed(),
+            extension: self.extension,
+            time_up: self.time_up,
+            reopened: self.reopened,
+        });
+        self.paused += self.current_pause().unwrap_or_default();
+        self.paused_at = None;
+        self.reopened = false;
+        self.clock.reset();
+        self.extension = Extension::default();
+        self.time_up = false;
+    }
+
+    /// Adds `amount` on top of whatever time is left, a phase whose time is
+    /// up gets `amount` from now.
+    pub fn extend(&mut self, amount: Duration) -> Result<()> {
+        let planned = self.durations.of(self.mode);
+        self.extension.add(planned, self.elapsed(), amount);
+        self.time_up = false;
+        self.reopened = false;
+        self.emit(Event::Extended(amount))
+    }
+
+    /// Changes how long `mode` lasts from now on, including the current
+    /// phase if it's a `mode` phase. Time already spent and extensions still
+    /// count, so a phase shortened past its elapsed time is up right away
+    /// and one lengthened past it runs again.
+    pub fn set_duration(&mut self, mode: Mode, duration: Duration) -> Result<()> {
+        match mode {
+            Mode::Work => self.durations.work = duration,
+            Mode::Break => self.durations.short_break = duration,
+            Mode::LongBreak => self.durations.long_break = duration,
+        }
+        if mode == self.mode && !self.is_time_up() {
+            self.time_up = false;
+        }
+        self.emit(Event::DurationChanged { mode, duration })
+    }
+
+    /// Takes time the user spent away off the current phase.
+    pub fn discard(&mut self, amount: Duration) {
+        self.clock.discard(amount);
+    }
+
+    pub fn interrupt(&mut self, interruption: Interruption) -> Result<()> {
+        self.interruptions.push(interruption.clone());
+        self.emit(Event::Interrupted(interruption))
+    }
+
+    pub fn set_task(&mut self, task: Option<String>) -> Result<()> {
+        self.task = task.clone();
+        self.emit(Event::TaskChanged(task))
+    }
+
+    /// Ends the current phase and starts the next one.
+    pub fn advance(&mut self) -> Result<()> {
+        self.end_phase(false)
+    }
+
+    /// Like [`Pomodoro::advance`], but the phase is recorded as skipped.
+    pub fn skip(&mut self) -> Result<()> {
+        self.end_phase(true)
+    }
+
+    /// Ends the current phase early and starts `mode` instead of the usual
+    /// next phase. Going to work starts a new session.
+    pub fn goto(&mut self, mode: Mode) -> Result<()> {
+        let skipped = !self.is_time_up();
+        self.end_phase_into(skipped, mode)
+    }
+
+    fn record(&mut self, skipped: bool) -> PhaseRecord {
+        PhaseRecord {
+            id: Uuid::new_v4(),
+            start: self.started_at,
+            end: self.time().local(),
+            mode: self.mode,
+            session: self.session,
+            planned: self.durations.of(self.mode),
+            elapsed: self.clock.elapsed(),
+            skipped,
+            extended: self.extension.added,
+            pauses: self.pauses,
+            paused: self.paused(),
+            interruptions: std::mem::take(&mut self.interruptions),
+            task: self.task.clone().filter(|_| self.mode == Mode::Work),
+            host: history::hostname(),
+        }
+    }
+
+    fn end_phase(&mut self, skipped: bool) -> Result<()> {
+        self.end_phase_into(skipped, self.next_mode())
+    }
+
+    fn end_phase_into(&mut self, skipped: bool, next: Mode) -> Result<()> {
+        let record = self.record(skipped);
+        self.records.push(record.clone());
+        self.ended.push(Ended {
+            phase: self.phase,
+            running: self.clock.is_running(),
+            extension: self.extension,
+            time_up: self.time_up,
+            long_break_at: self.long_break_at,
+        });
+        self.undo.push(Undo::PhaseChange);
+        self.emit(Event::PhaseEnded(record))?;
+
+        if next == Mode::LongBreak {
+            self.long_break_at = self.session + 4;
+        }
+        if next == Mode::Work {
+            self.session += 1;
+        }
+        self.mode = next;
+        self.phases += 1;
+        self.phase = self.phases;
+        self.clock.reset();
+        self.pauses = 0;
+        self.paused = Duration::ZERO;
+        self.paused_at = None;
+        self.extension = Extension::default();
+        self.started_at = self.time().local();
+        self.time_up = false;
+        self.reopened = false;
+        self.emit(Event::PhaseStarted {
+            mode: self.mode,
+            session: self.session,
+        })
+    }
+
+    /// Drops the current phase and picks up the one before it where it was
+    /// left, taking its record back. Returns false on the first phase.
+    pub fn back(&mut self) -> Result<bool> {
+        let (Some(record), Some(ended)) = (self.records.pop(), self.ended.pop()) else {
+            return Ok(false);
+        };
+        // Resets of the dropped phase can't be undone anymore.
+        if let Some(i) = self
+            .undo
+            .iter()
+            .rposition(|undo| matches!(undo, Undo::PhaseChange))
+        {
+            self.undo.truncate(i);
+        }
+        self.mode = record.mode;
+        self.session = record.session;
+        self.phase = ended.phase;
+        self.clock = Clock::resumed(self.time().clone(), record.elapsed, ended.running);
+        self.time_up = ended.time_up;
+        self.reopened = true;
+        self.long_break_at = ended.long_break_at;
+        self.extension = ended.extension;
+        self.pauses = record.pauses;
+        self.paused = record.paused;
+        self.paused_at = (!ended.running).then(|| self.time().now());
+        self.interruptions = record.interruptions.clone();
+        self.started_at = record.start;
+        self.emit(Event::PhaseReopened(record))?;
+        Ok(true)
+    }
+
+    /// Takes back the last reset or phase change. An undone reset keeps the
+    /// time spent since, an undone phase change works like
+    /// [`Pomodoro::back`].
+    pub fn undo(&mut self) -> Result<Option<Undone>> {
+        match self.undo.last().copied() {
+            None => Ok(None),
+            Some(Undo::PhaseChange) => {
+                self.back()?;
+                Ok(Some(Undone::PhaseChange))
+            }
+            Some(Undo::Reset {
+                elapsed,
+                extension,
+                time_up,
+                reopened,
+            }) => {
+                self.undo.pop();
+                let elapsed = elapsed + self.clock.elapsed();
+                self.clock = Clock::resumed(self.time().clone(), elapsed, self.clock.is_running());
+                self.extension = extension;
+                self.time_up = time_up;
+                self.reopened = reopened;
+                Ok(Some(Undone::Reset))
+            }
+        }
+    }
+
+    /// Records the unfinished phase and returns every finished one.
+    pub fn finish(mut self) -> Result<Vec<PhaseRecord>> {
+        if self.clock.elapsed() > Duration::ZERO {
+            let record = self.record(false);
+            self.records.push(record.clone());
+            self.emit(Event::PhaseEnded(record))?;
+        }
+        Ok(self.records)
+    }
+}
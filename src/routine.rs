@@ -0,0 +1,227 @@
+//! User-defined phase sequences, configured under `[routines]`.
+
+use std::time::Duration;
+
+use serde::{de, Deserialize, Deserializer};
+
+use crate::clock::{Clock, TimeSource};
+use crate::config::duration;
+use crate::prelude::*;
+
+/// How many times something runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeat {
+    Times(u32),
+    Forever,
+}
+
+impl Default for Repeat {
+    fn default() -> Self {
+        Self::Times(1)
+    }
+}
+
+impl Repeat {
+    /// Whether another run should follow run number `done`.
+    pub fn continues_after(&self, done: u32) -> bool {
+        match self {
+            Self::Times(times) => done < *times,
+            Self::Forever => true,
+        }
+    }
+}
+
+/// Parses a count or `forever`.
+pub fn parse_repeat(text: &str) -> Result<Repeat> {
+    if text == "forever" {
+        return Ok(Repeat::Forever);
+    }
+    match text.parse::<u32>()? {
+        0 => Err(anyhow!("Should repeat at least once.")),
+        times => Ok(Repeat::Times(times)),
+    }
+}
+
+impl<'de> Deserialize<'de> for Repeat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Times(u32),
+            Text(String),
+        }
+        match Raw::deserialize(deserializer)? {
+            Raw::Times(times) => parse_repeat(&times.to_string()),
+            Raw::Text(text) => parse_repeat(&text),
+        }
+        .map_err(de::Error::custom)
+    }
+}
+
+/// What to play when a phase is over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sound {
+    /// The usual notification sound.
+    #[default]
+    Bell,
+    High,
+    Low,
+    None,
+}
+
+/// One phase of a routine.
+///
+/// ```toml
+/// [[routines.ultradian.phases]]
+/// name = "deep work"
+/// duration = "90m"
+/// color = "red"
+/// sound = "high"
+/// auto_advance = true
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PhaseConfig {
+    pub name: String,
+    #[serde(with = "duration")]
+    pub duration: Duration,
+    /// A terminal color name such as `red` or `dark_cyan`.
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub sound: Sound,
+    /// Start the next phase as soon as this one is over.
+    #[serde(default)]
+    pub auto_advance: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RoutineConfig {
+    pub phases: Vec<PhaseConfig>,
+    /// How many times the whole sequence runs, a count or `"forever"`.
+    #[serde(default)]
+    pub repeat: Repeat,
+}
+
+pub struct Routine {
+    phases: Vec<PhaseConfig>,
+    repeat: Repeat,
+    clock: Clock,
+    index: usize,
+    round: u32,
+    time_up: bool,
+    finished: bool,
+}
+
+impl Routine {
+    pub fn new(config: RoutineConfig) -> Result<Self> {
+        if config.phases.is_empty() {
+            return Err(anyhow!("A routine needs at least one phase."));
+        }
+        Ok(Self {
+            phases: config.phases,
+            repeat: config.repeat,
+            clock: Clock::default(),
+            index: 0,
+            round: 1,
+            time_up: false,
+            finished: false,
+        })
+    }
+
+    /// Reads the time from `time` instead of the system.
+    pub fn with_time(mut self, time: TimeSource) -> Self {
+        self.clock = Clock::new(time);
+        self
+    }
+
+    pub fn phases(&self) -> &[PhaseConfig] {
+        &self.phases
+    }
+
+    pub fn phase(&self) -> &PhaseConfig {
+        &self.phases[self.index]
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn repeat(&self) -> Repeat {
+        self.repeat
+    }
+
+    /// Runs through the sequence, counted from 1.
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    /// The phase after this one, `None` at the very end.
+    pub fn next_phase(&self) -> Option<&PhaseConfig> {
+        match self.phases.get(self.index + 1) {
+            Some(phase) => Some(phase),
+            None if self.repeat.continues_after(self.round) => self.phases.first(),
+            None => None,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.clock.is_running()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.phase().duration.saturating_sub(self.clock.elapsed())
+    }
+
+    pub fn overtime(&self) -> Duration {
+        self.clock.elapsed().saturating_sub(self.phase().duration)
+    }
+
+    pub fn is_time_up(&self) -> bool {
+        self.clock.elapsed() >= self.phase().duration
+    }
+
+    /// Checks the clock, to be called regularly. Returns the phase whose time
+    /// just ran out, once per phase, after moving on if it auto advances.
+    pub fn tick(&mut self) -> Option<PhaseConfig> {
+        if self.finished || self.time_up || !self.is_time_up() {
+            return None;
+        }
+        self.time_up = true;
+        let phase = self.phase().clone();
+        if phase.auto_advance {
+            self.advance();
+        }
+        Some(phase)
+    }
+
+    pub fn toggle(&mut self) {
+        self.clock.toggle();
+    }
+
+    /// Restarts the current phase.
+    pub fn reset(&mut self) {
+        self.clock.reset();
+        self.time_up = false;
+    }
+
+    /// Starts the next phase, or finishes the routine after the last one.
+    pub fn advance(&mut self) {
+        if self.index + 1 < self.phases.len() {
+            self.index += 1;
+        } else if self.repeat.continues_after(self.round) {
+            self.index = 0;
+            self.round += 1;
+        } else {
+            self.finished = true;
+            return;
+        }
+        self.reset();
+    }
+}
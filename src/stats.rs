@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::Local;
+
+use porsmo::format::format_duration_short;
+use porsmo::goal::{self, GoalConfig};
+use porsmo::history;
+use porsmo::pomodoro::Mode;
+use porsmo::prelude::*;
+
+const NO_TASK: &str = "(no task)";
+
+pub fn print_stats(goal: &GoalConfig) -> Result<()> {
+    let records = history::load()?;
+
+    let mut by_task: HashMap<&str, (Duration, u32)> = HashMap::new();
+    for record in records.iter().filter(|r| r.mode == Mode::Work) {
+        let task = record.task.as_deref().unwrap_or(NO_TASK);
+        let entry = by_task.entry(task).or_default();
+        entry.0 += record.elapsed;
+        if !record.skipped {
+            entry.1 += 1;
+        }
+    }
+
+    if by_task.is_empty() {
+        println!("No pomodoros recorded yet.");
+        return Ok(());
+    }
+
+    let mut by_task: Vec<_> = by_task.into_iter().collect();
+    by_task.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(a.0.cmp(b.0)));
+
+    let width = by_task
+        .iter()
+        .map(|(task, _)| task.len())
+        .max()
+        .unwrap_or(0);
+    let total: Duration = by_task.iter().map(|(_, (time, _))| *time).sum();
+
+    println!("Focus time by task:");
+    for (task, (time, pomodoros)) in &by_task {
+        let plural = if *pomodoros == 1 { "" } else { "s" };
+        println!(
+            "  {task:width$}  {} ({pomodoros} pomodoro{plural})",
+            format_duration_short(*time),
+        );
+    }
+    println!("Total: {}", format_duration_short(total));
+
+    if let Some(today) = goal.describe(&goal::today(&records)) {
+        let streaks = goal::streaks(&records, goal, Local::now().date_naive());
+        let days = |n: u32| {
+            if n == 1 {
+                "1 day".to_string()
+            } else {
+                format!("{n} days")
+            }
+        };
+        println!("{today}");
+        println!(
+            "Goal streak: {} (best {})",
+            days(streaks.current),
+            days(streaks.best)
+        );
+    }
+
+    Ok(())
+}
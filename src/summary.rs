@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use crate::format::format_duration_short;
+use crate::history::{Interruption, InterruptionKind, PhaseRecord};
+use crate::pomodoro::Mode;
+
+pub const DEFAULT_TEMPLATE: &str = "You have worked for {worked}, and rested {rested}.";
+
+#[derive(Debug, Default)]
+pub struct Summary {
+    records: Vec<PhaseRecord>,
+}
+
+impl From<Vec<PhaseRecord>> for Summary {
+    fn from(records: Vec<PhaseRecord>) -> Self {
+        Self { records }
+    }
+}
+
+impl Summary {
+    pub fn worked(&self) -> Duration {
+        self.work_records().map(|r| r.elapsed).sum()
+    }
+
+    pub fn rested(&self) -> Duration {
+        self.break_records().map(|r| r.elapsed).sum()
+    }
+
+    pub fn overtime(&self) -> Duration {
+        self.work_records().map(PhaseRecord::overtime).sum()
+    }
+
+    pub fn extended(&self) -> Duration {
+        self.records.iter().map(|r| r.extended).sum()
+    }
+
+    pub fn sessions(&self) -> u32 {
+        self.work_records().count() as u32
+    }
+
+    pub fn breaks(&self) -> u32 {
+        self.break_records().filter(|r| !r.skipped).count() as u32
+    }
+
+    pub fn skips(&self) -> u32 {
+        self.records.iter().filter(|r| r.skipped).count() as u32
+    }
+
+    pub fn pauses(&self) -> u32 {
+        self.records.iter().map(|r| r.pauses).sum()
+    }
+
+    pub fn paused(&self) -> Duration {
+        self.records.iter().map(|r| r.paused).sum()
+    }
+
+    pub fn interruptions(&self) -> u32 {
+        self.records
+            .iter()
+            .map(|r| r.interruptions.len() as u32)
+            .sum()
+    }
+
+    fn work_records(&self) -> impl Iterator<Item = &PhaseRecord> {
+        self.records.iter().filter(|r| r.mode == Mode::Work)
+    }
+
+    fn break_records(&self) -> impl Iterator<Item = &PhaseRecord> {
+        self.records.iter().filter(|r| r.mode != Mode::Work)
+    }
+
+    /// One line per pomodoro session, e.g.
+    /// `Session 2: work 27m 3s of 25m 0s (+2m 3s overtime), break 5m 0s of 5m 0s, 1 pause`
+    pub fn breakdown(&self) -> String {
+        let mut lines = Vec::new();
+        let last_session = self.records.iter().map(|r| r.session).max().unwrap_or(0);
+
+        for session in 1..=last_session {
+            let parts: Vec<String> = self
+                .records
+                .iter()
+                .filter(|r| r.session == session)
+                .map(|r| {
+                    let name = match r.mode {
+                        Mode::Work => "work",
+                        Mode::Break => "break",
+                        Mode::LongBreak => "long break",
+                    };
+                    let mut part = format!(
+                        "{name} {} of {}",
+                        format_duration_short(r.elapsed),
+                        format_duration_short(r.planned),
+                    );
+                    if r.extended > Duration::ZERO {
+                        part += &format!(" (+{} extended)", format_duration_short(r.extended));
+                    }
+                    if r.mode == Mode::Work && r.overtime().as_secs() > 0 {
+                        part += &format!(" (+{} overtime)", format_duration_short(r.overtime()));
+                    }
+                    if r.skipped {
+                        part += " (skipped)";
+                    }
+                    part
+                })
+                .collect();
+
+            if parts.is_empty() {
+                continue;
+            }
+
+            let session_records = self.records.iter().filter(|r| r.session == session);
+            let pauses: u32 = session_records.clone().map(|r| r.pauses).sum();
+            let paused: Duration = session_records.map(|r| r.paused).sum();
+            let pauses = match pauses {
+                0 => String::from("no pauses"),
+                1 => format!("1 pause ({})", format_duration_short(paused)),
+                n => format!("{n} pauses ({})", format_duration_short(paused)),
+            };
+
+            let interruptions: Vec<&Interruption> = self
+                .records
+                .iter()
+                .filter(|r| r.session == session)
+                .flat_map(|r| &r.interruptions)
+                .collect();
+            let mut line = format!("Session {session}: {}, {pauses}", parts.join(", "));
+            if !interruptions.is_empty() {
+                let count = |kind| interruptions.iter().filter(|i| i.kind == kind).count();
+                line += &format!(
+                    ", interrupted {} internally and {} externally",
+                    count(InterruptionKind::Internal),
+                    count(InterruptionKind::External),
+                );
+                for interruption in interruptions {
+                    if let Some(note) = &interruption.note {
+                        line += &format!(
+                            "\n  {} at {}: {note}",
+                            interruption.kind,
+                            format_duration_short(interruption.at),
+                        );
+                    }
+                }
+            }
+
+            lines.push(line);
+        }
+
+        lines.join("\n")
+    }
+
+    /// Fills in the placeholders of a user provided exit message:
+    /// `{worked}`, `{rested}`, `{overtime}`, `{extended}`, `{sessions}`, `{breaks}`,
+    /// `{skips}`, `{pauses}`, `{paused}` and `{interruptions}`.
+    pub fn render(&self, template: &str) -> String {
+        template
+            .replace("{worked}", &format_duration_short(self.worked()))
+            .replace("{rested}", &format_duration_short(self.rested()))
+            .replace("{overtime}", &format_duration_short(self.overtime()))
+            .replace("{extended}", &format_duration_short(self.extended()))
+            .replace("{sessions}", &self.sessions().to_string())
+            .replace("{breaks}", &self.breaks().to_string())
+            .replace("{skips}", &self.skips().to_string())
+            .replace("{pauses}", &self.pauses().to_string())
+            .replace("{paused}", &format_duration_short(self.paused()))
+            .replace("{interruptions}", &self.interruptions().to_string())
+    }
+}
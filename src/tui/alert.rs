@@ -0,0 +1,114 @@
+use std::io::{self, BufReader, Cursor, Write};
+use std::time::Duration;
+
+use rodio::mixer::Mixer;
+use rodio::source::{SineWave, Source};
+use rodio::{OutputStream, OutputStreamBuilder, Sink};
+
+use crate::interval::Cue;
+use crate::prelude::*;
+use crate::routine::Sound;
+
+const FILE: &[u8] = include_bytes!("notify_end.wav");
+
+/// Plays the notification sound. The audio device is only opened once the
+/// first alert fires, if that fails (e.g. on a machine without sound) the
+/// terminal bell rings instead from then on.
+#[derive(Default)]
+pub struct Alerts {
+    stream_handle: Option<OutputStream>,
+    // Makes sure that the sink doesn't disappear while it's playing.
+    _sink: Option<Sink>,
+    silent: bool,
+    /// Every alert so far, the notification sound counts as
+    /// [`Cue::Finished`].
+    played: Vec<Cue>,
+    /// Why the sound couldn't be played.
+    error: Option<String>,
+}
+
+impl Alerts {
+    /// Records alerts without touching the audio device.
+    pub fn silent() -> Self {
+        Self {
+            silent: true,
+            ..Self::default()
+        }
+    }
+
+    /// How many alerts fired so far.
+    pub fn played(&self) -> usize {
+        self.played.len()
+    }
+
+    /// Every alert so far, in order.
+    pub fn cues(&self) -> &[Cue] {
+        &self.played
+    }
+
+    /// Why alerts fell back to the terminal bell, if they did.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn mixer(&mut self) -> Result<&Mixer> {
+        if self.stream_handle.is_none() {
+            let mut stream_handle = OutputStreamBuilder::open_default_stream()?;
+            stream_handle.log_on_drop(false);
+            self.stream_handle = Some(stream_handle);
+        }
+        Ok(self.stream_handle.as_ref().unwrap().mixer())
+    }
+
+    /// Runs `play` on the audio device, or rings the bell if there's none.
+    fn output(&mut self, play: impl FnOnce(&mut Self) -> Result<()>) {
+        if self.silent {
+            return;
+        }
+        if self.error.is_none() {
+            match play(self) {
+                Ok(()) => return,
+                Err(e) => self.error = Some(format!("{e:#}")),
+            }
+        }
+        let mut stdout = io::stdout();
+        let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+    }
+
+    pub fn play(&mut self) {
+        self.played.push(Cue::Finished);
+        self.output(|alerts| {
+            let file = BufReader::new(Cursor::new(FILE));
+            alerts._sink = Some(rodio::play(alerts.mixer()?, file)?);
+            Ok(())
+        });
+    }
+
+    /// Plays a short tone for the cue, [`Cue::Finished`] gets the usual
+    /// notification sound.
+    pub fn cue(&mut self, cue: Cue) {
+        let (freq, millis) = match cue {
+            Cue::Start => (880.0, 400),
+            Cue::End => (440.0, 600),
+            Cue::Countdown => (660.0, 120),
+            Cue::Finished => return self.play(),
+        };
+        self.played.push(cue);
+        self.output(|alerts| {
+            let tone = SineWave::new(freq)
+                .take_duration(Duration::from_millis(millis))
+                .amplify(0.2);
+            alerts.mixer()?.add(tone);
+            Ok(())
+        });
+    }
+
+    pub fn sound(&mut self, sound: Sound) {
+        match sound {
+            Sound::Bell => self.play(),
+            Sound::High => self.cue(Cue::Start),
+            Sound::Low => self.cue(Cue::End),
+            Sound::None => {}
+        }
+    }
+}
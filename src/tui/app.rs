@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use crate::config::TerminalConfig;
+use crate::prelude::*;
+use crate::tui::alert::Alerts;
+use crate::tui::input::{Input, TerminalInput};
+use crate::tui::terminal::TerminalHandler;
+use crate::tui::ui::{Frame, Screen};
+
+pub const TIMEOUT: Duration = Duration::from_millis(250);
+
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+/// Anything that can be driven by [`run`]: the stopwatch, the timer and the
+/// pomodoro.
+pub trait Counter {
+    /// Advances the state, called before every redraw.
+    fn tick(&mut self, alerts: &mut Alerts) -> Result<()>;
+
+    fn render(&self, frame: &mut Frame);
+
+    /// Only key presses without Ctrl or Alt reach the counter.
+    fn handle_action(&mut self, key: KeyEvent) -> Result<Flow>;
+
+    /// The terminal gained or lost focus.
+    fn handle_focus(&mut self, _focused: bool) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Takes over the terminal and runs the counter until it asks to quit.
+/// The terminal is restored before returning.
+pub fn run(counter: &mut impl Counter, config: &TerminalConfig) -> Result<()> {
+    let mut alerts = Alerts::default();
+    let result = TerminalHandler::new(config)
+        .and_then(|mut terminal| run_with(counter, &mut terminal, &mut TerminalInput, &mut alerts));
+    if let Some(error) = alerts.error() {
+        eprintln!("Could not play sounds, rang the terminal bell instead: {error}");
+    }
+    result
+}
+
+/// Runs the counter against any screen and input, e.g. a
+/// [`MemoryScreen`](crate::tui::ui::MemoryScreen) and a
+/// [`Script`](crate::tui::input::Script).
+pub fn run_with(
+    counter: &mut impl Counter,
+    screen: &mut impl Screen,
+    input: &mut impl Input,
+    alerts: &mut Alerts,
+) -> Result<()> {
+    loop {
+        counter.tick(alerts)?;
+        let mut frame = Frame::default();
+        counter.render(&mut frame);
+        screen.draw(&frame)?;
+
+        let key = match input.next_event(TIMEOUT)? {
+            Some(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Some(Event::Mouse(mouse)) => match mouse_key(mouse, screen) {
+                Some(key) => key,
+                None => continue,
+            },
+            Some(Event::FocusGained) => {
+                counter.handle_focus(true)?;
+                continue;
+            }
+            Some(Event::FocusLost) => {
+                counter.handle_focus(false)?;
+                continue;
+            }
+            // Redrawn right away at the top of the loop.
+            Some(Event::Resize(width, height)) => {
+                screen.resize(width, height);
+                continue;
+            }
+            _ => continue,
+        };
+        if key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            continue;
+        }
+        if let Flow::Quit = counter.handle_action(key)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Clicking a button presses its key, scrolling presses the arrow keys.
+fn mouse_key(mouse: MouseEvent, screen: &impl Screen) -> Option<KeyEvent> {
+    let code = match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => screen.button_at(mouse.column, mouse.row)?,
+        MouseEventKind::ScrollUp => KeyCode::Up,
+        MouseEventKind::ScrollDown => KeyCode::Down,
+        _ => return None,
+    };
+    Some(KeyEvent::new(code, KeyModifiers::NONE))
+}
@@ -0,0 +1,92 @@
+use std::time::{Duration, Instant};
+
+use crate::clock::TimeSource;
+use crate::idle::{system_idle, IdleConfig, IdleSource};
+
+/// How often the system is asked for the idle time.
+const POLL: Duration = Duration::from_secs(5);
+
+/// Tracks how long the user has been away from the keyboard.
+pub struct IdleWatch {
+    config: IdleConfig,
+    time: TimeSource,
+    last_activity: Instant,
+    /// The last answer from the system and when it was given.
+    polled: Option<(Instant, Duration)>,
+    /// Set once asking the system failed, it's only reported the first time.
+    failed: bool,
+    error: Option<String>,
+}
+
+impl IdleWatch {
+    pub fn new(config: IdleConfig) -> Self {
+        let time = TimeSource::default();
+        Self {
+            config,
+            last_activity: time.now(),
+            time,
+            polled: None,
+            failed: false,
+            error: None,
+        }
+    }
+
+    /// Reads the time from `time` instead of the system.
+    pub fn with_time(mut self, time: TimeSource) -> Self {
+        self.last_activity = time.now();
+        self.time = time;
+        self
+    }
+
+    /// Something happened in the terminal. With the terminal source, returns
+    /// how long nothing happened before if that reached the threshold.
+    pub fn activity(&mut self) -> Option<Duration> {
+        let now = self.time.now();
+        let idle = now - std::mem::replace(&mut self.last_activity, now);
+        self.polled = None;
+        let after = self.config.after?;
+        (self.config.source == IdleSource::Terminal && idle >= after).then_some(idle)
+    }
+
+    /// The system's idle time once it reached the configured threshold,
+    /// always `None` when idle detection is off. The terminal source is
+    /// only noticed once the user is back, see [`IdleWatch::activity`]: the
+    /// terminal can't tell someone away from someone working elsewhere.
+    /// The user counts as present while the system can't tell, see
+    /// [`IdleWatch::take_error`].
+    pub fn away(&mut self) -> Option<Duration> {
+        let after = self.config.after?;
+        if self.config.source == IdleSource::Terminal {
+            return None;
+        }
+        let now = self.time.now();
+        let (polled_at, idle) = match self.polled {
+            Some((polled_at, idle)) if now - polled_at < POLL => (polled_at, idle),
+            _ => {
+                let idle = self.poll();
+                *self.polled.insert((now, idle))
+            }
+        };
+        let idle = idle + (now - polled_at);
+        (idle >= after).then_some(idle)
+    }
+
+    fn poll(&mut self) -> Duration {
+        match system_idle(&self.config) {
+            Ok(idle) => idle,
+            Err(e) => {
+                if !self.failed {
+                    self.failed = true;
+                    self.error = Some(format!("Idle detection failed: {e:#}"));
+                }
+                Duration::ZERO
+            }
+        }
+    }
+
+    /// Why the system couldn't tell the idle time, only the first time it
+    /// failed.
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+}
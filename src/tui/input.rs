@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+
+use crate::clock::TimeSource;
+use crate::prelude::*;
+
+/// Where key presses and other terminal events come from.
+pub trait Input {
+    /// Waits up to `timeout` for the next event.
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>>;
+}
+
+/// Reads events from the terminal.
+pub struct TerminalInput;
+
+impl Input for TerminalInput {
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        if !event::poll(timeout)? {
+            return Ok(None);
+        }
+        Ok(Some(event::read()?))
+    }
+}
+
+/// Replays events at fixed offsets from its creation. Instead of sleeping,
+/// waiting moves the script's manual [`TimeSource`] forward, so a scripted
+/// 25 minute pomodoro driven by that time finishes instantly.
+///
+/// Once every event was replayed, asking for another one is an error so a
+/// script that forgets to quit can't hang.
+pub struct Script {
+    time: TimeSource,
+    start: Instant,
+    events: VecDeque<(Duration, Event)>,
+}
+
+impl Default for Script {
+    fn default() -> Self {
+        Self::starting_at(Local::now())
+    }
+}
+
+impl Script {
+    /// A script whose time starts at `local`.
+    pub fn starting_at(local: DateTime<Local>) -> Self {
+        let time = TimeSource::manual(local);
+        Self {
+            start: time.now(),
+            time,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// The time to give the counter the script drives.
+    pub fn time(&self) -> TimeSource {
+        self.time.clone()
+    }
+
+    /// Presses `code` once `at` has passed since the script was created.
+    pub fn press(self, at: Duration, code: KeyCode) -> Self {
+        self.event(at, Event::Key(KeyEvent::new(code, KeyModifiers::NONE)))
+    }
+
+    /// The terminal gains or loses focus.
+    pub fn focus(self, at: Duration, focused: bool) -> Self {
+        match focused {
+            true => self.event(at, Event::FocusGained),
+            false => self.event(at, Event::FocusLost),
+        }
+    }
+
+    /// Clicks the left mouse button at `column` and `row`.
+    pub fn click(self, at: Duration, column: u16, row: u16) -> Self {
+        self.mouse(at, MouseEventKind::Down(MouseButton::Left), column, row)
+    }
+
+    pub fn scroll(self, at: Duration, up: bool) -> Self {
+        let kind = match up {
+            true => MouseEventKind::ScrollUp,
+            false => MouseEventKind::ScrollDown,
+        };
+        self.mouse(at, kind, 0, 0)
+    }
+
+    fn mouse(self, at: Duration, kind: MouseEventKind, column: u16, row: u16) -> Self {
+        let mouse = MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        self.event(at, Event::Mouse(mouse))
+    }
+
+    pub fn event(mut self, at: Duration, event: Event) -> Self {
+        self.events.push_back((at, event));
+        self
+    }
+
+    /// Types every character of `text` at once.
+    pub fn type_text(mut self, at: Duration, text: &str) -> Self {
+        for c in text.chars() {
+            self = self.press(at, KeyCode::Char(c));
+        }
+        self
+    }
+}
+
+impl Input for Script {
+    fn next_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        let now = self.time.now();
+        let Some(&(at, _)) = self.events.front() else {
+            return Err(anyhow!("The input script ran out of events"));
+        };
+        let due = (self.start + at).saturating_duration_since(now);
+        if due > timeout {
+            self.time.advance(timeout);
+            return Ok(None);
+        }
+        self.time.advance(due);
+        Ok(self.events.pop_front().map(|(_, event)| event))
+    }
+}
@@ -0,0 +1,95 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::style::{Color, Stylize};
+
+use crate::clock::TimeSource;
+use crate::format::{format_clock, format_duration};
+use crate::interval::{Interval, Phase, Plan};
+use crate::prelude::*;
+use crate::tui::alert::Alerts;
+use crate::tui::app::{Counter, Flow};
+use crate::tui::terminal::running_color;
+use crate::tui::ui::{show_ui, Frame};
+
+const CONTROLS: &str = "[Q]: quit, [Space]: pause/resume, [R]: Restart";
+
+pub struct IntervalApp {
+    interval: Interval,
+}
+
+impl IntervalApp {
+    pub fn new(plan: Plan) -> Self {
+        Self {
+            interval: Interval::new(plan),
+        }
+    }
+
+    /// Reads the time from `time` instead of the system.
+    pub fn with_time(mut self, time: TimeSource) -> Self {
+        self.interval = self.interval.with_time(time);
+        self
+    }
+}
+
+impl Counter for IntervalApp {
+    fn tick(&mut self, alerts: &mut Alerts) -> Result<()> {
+        for cue in self.interval.tick() {
+            alerts.cue(cue);
+        }
+        Ok(())
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let rounds = self.interval.plan().rounds;
+        let Some((_, step, remaining)) = self.interval.current() else {
+            frame.set_title("🏁 Workout complete");
+            show_ui(
+                frame,
+                "Workout complete!".with(Color::Green),
+                format_duration(self.interval.total()),
+                CONTROLS,
+            );
+            frame.push(format!("Rounds: {rounds}"));
+            return;
+        };
+
+        let title = match step.phase {
+            Phase::Warmup => "Warm up".with(Color::Yellow),
+            Phase::Work => "Work!".with(Color::Red),
+            Phase::Rest => "Rest".with(Color::Blue),
+            Phase::Cooldown => "Cool down".with(Color::Yellow),
+        };
+        let name = match step.phase {
+            Phase::Warmup => "Warm up".to_string(),
+            Phase::Work => format!("Work {}/{rounds}", step.round),
+            Phase::Rest => format!("Rest {}/{rounds}", step.round),
+            Phase::Cooldown => "Cool down".to_string(),
+        };
+        frame.set_title(format!("🏃 {} {name}", format_clock(remaining)));
+        frame.set_progress(self.interval.elapsed(), self.interval.total());
+        let color = running_color(self.interval.is_running());
+        show_ui(
+            frame,
+            title,
+            format_duration(remaining).with(color),
+            CONTROLS,
+        );
+        if step.round > 0 {
+            frame.push(format!("Round: {}/{rounds}", step.round));
+        }
+        let total_left = self
+            .interval
+            .total()
+            .saturating_sub(self.interval.elapsed());
+        frame.push(format!("Total left: {}", format_duration(total_left)));
+    }
+
+    fn handle_action(&mut self, key: KeyEvent) -> Result<Flow> {
+        match key.code {
+            KeyCode::Char('q') => return Ok(Flow::Quit),
+            KeyCode::Char(' ') => self.interval.toggle(),
+            KeyCode::Char('r') => self.interval.reset(),
+            _ => {}
+        }
+        Ok(Flow::Continue)
+    }
+}
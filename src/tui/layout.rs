@@ -0,0 +1,193 @@
+//! Fitting a [`Frame`](crate::tui::ui::Frame) into the terminal.
+
+use crossterm::event::KeyCode;
+
+use crate::tui::ui::Line;
+
+/// Narrower terminals get the compact layout.
+pub const COMPACT_WIDTH: u16 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// Top left, one row per line, anything that doesn't fit is cut off.
+    Compact,
+    /// Centered, long lines wrap between their comma separated parts.
+    Expanded,
+}
+
+/// A line placed at a column and row of the terminal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placed {
+    pub x: u16,
+    pub y: u16,
+    pub line: Line,
+}
+
+/// Expanded when everything fits once wrapped, compact otherwise.
+pub fn choose(lines: &[Line], width: u16, height: u16) -> Layout {
+    let rows: usize = lines.iter().map(|line| wrap(line, width).len()).sum();
+    match width >= COMPACT_WIDTH && rows <= height as usize {
+        true => Layout::Expanded,
+        false => Layout::Compact,
+    }
+}
+
+pub fn lay_out(lines: &[Line], width: u16, height: u16) -> Vec<Placed> {
+    match choose(lines, width, height) {
+        Layout::Compact => lines
+            .iter()
+            .take(height as usize)
+            .enumerate()
+            .map(|(y, line)| Placed {
+                x: 0,
+                y: y as u16,
+                line: Line {
+                    text: truncate(&line.text, width),
+                    color: line.color,
+                },
+            })
+            .collect(),
+        Layout::Expanded => {
+            let rows: Vec<Line> = lines.iter().flat_map(|line| wrap(line, width)).collect();
+            let top = (height as usize - rows.len()) / 2;
+            rows.into_iter()
+                .enumerate()
+                .map(|(y, line)| Placed {
+                    x: (width - text_width(&line.text)) / 2,
+                    y: (top + y) as u16,
+                    line,
+                })
+                .collect()
+        }
+    }
+}
+
+/// Splits `line` at its `, ` separators into rows no wider than `width`,
+/// parts that are too long by themselves are truncated.
+fn wrap(line: &Line, width: u16) -> Vec<Line> {
+    let row = |text: String| Line {
+        text: truncate(&text, width),
+        color: line.color,
+    };
+    if text_width(&line.text) <= width {
+        return vec![row(line.text.clone())];
+    }
+
+    let mut rows = Vec::new();
+    let mut current = String::new();
+    for part in line.text.split(", ") {
+        if current.is_empty() {
+            current = part.to_string();
+        } else if text_width(&current) + 2 + text_width(part) <= width {
+            current = format!("{current}, {part}");
+        } else {
+            rows.push(row(current));
+            current = part.to_string();
+        }
+    }
+    rows.push(row(current));
+    rows
+}
+
+/// Cuts `text` down to `width` columns, ending in `…` if anything was cut.
+pub fn truncate(text: &str, width: u16) -> String {
+    if text_width(text) <= width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = char_width(c);
+        if used + w + 1 > width {
+            break;
+        }
+        truncated.push(c);
+        used += w;
+    }
+    if width > 0 {
+        truncated.push('…');
+    }
+    truncated
+}
+
+/// How many columns `text` takes up in the terminal.
+pub fn text_width(text: &str) -> u16 {
+    text.chars().map(char_width).sum()
+}
+
+/// Emoji and East Asian wide characters take two columns, joiners and
+/// variation selectors none. Close enough for what the counters draw.
+fn char_width(c: char) -> u16 {
+    match c as u32 {
+        0x200B..=0x200F | 0xFE00..=0xFE0F | 0x0300..=0x036F => 0,
+        0x1100..=0x115F
+        | 0x231A..=0x231B
+        | 0x23E9..=0x23F3
+        | 0x2614..=0x2615
+        | 0x26A1
+        | 0x26BD..=0x26BE
+        | 0x2705
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F900..=0x1F9FF
+        | 0x1FA70..=0x1FAFF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// The key behind the button at `column` and `row`. Every `[K]: label` part
+/// of a line is a button, and `[+/>]: +1m/+5m` is one button per label.
+pub fn button_at(placed: &[Placed], column: u16, row: u16) -> Option<KeyCode> {
+    let placed = placed.iter().find(|p| p.y == row && p.x <= column)?;
+    let offset = column - placed.x;
+    let mut start = 0;
+    for part in placed.line.text.split(", ") {
+        let width = text_width(part);
+        if offset < start {
+            return None;
+        }
+        if offset < start + width {
+            return button(part, offset - start);
+        }
+        start += width + 2;
+    }
+    None
+}
+
+fn button(part: &str, offset: u16) -> Option<KeyCode> {
+    let (keys, label) = part.strip_prefix('[')?.split_once("]: ")?;
+    let keys: Vec<&str> = keys.split('/').collect();
+    let labels: Vec<&str> = label.split('/').collect();
+    let mut index = 0;
+    if keys.len() == labels.len() {
+        // Past the `[`, the keys and the `]: `.
+        let mut end = text_width(&keys.join("/")) + 4;
+        for (i, label) in labels.iter().enumerate() {
+            end += text_width(label) + 1;
+            if offset < end {
+                index = i;
+                break;
+            }
+        }
+    }
+    match keys[index] {
+        "Space" => Some(KeyCode::Char(' ')),
+        "Enter" => Some(KeyCode::Enter),
+        "Esc" => Some(KeyCode::Esc),
+        key => {
+            let mut chars = key.chars();
+            let c = chars.next()?;
+            chars
+                .next()
+                .is_none()
+                .then(|| KeyCode::Char(c.to_ascii_lowercase()))
+        }
+    }
+}
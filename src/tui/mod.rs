@@ -0,0 +1,17 @@
+//! The terminal front end: a runtime that owns the terminal and the audio,
+//! and the counters it can drive.
+
+pub mod alert;
+pub mod app;
+pub mod idle;
+pub mod input;
+pub mod interval;
+pub mod layout;
+pub mod pomodoro;
+pub mod routine;
+pub mod stopwatch;
+pub mod terminal;
+pub mod timer;
+pub mod timers;
+pub mod ui;
+pub mod warning;
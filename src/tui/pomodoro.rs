@@ -0,0 +1,702 @@
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::style::{Color, Stylize};
+
+use crate::clock::TimeSource;
+use crate::command::{parse_command, Command, USAGE};
+use crate::config::{Config, ExtendConfig};
+use crate::format::{format_clock, format_duration, format_duration_short};
+use crate::goal::{GoalConfig, Progress};
+use crate::history::{HistoryWriter, Interruption, InterruptionKind, PhaseRecord};
+use crate::hooks;
+use crate::pomodoro::{Durations, Event, Mode, Observer, Pomodoro, Undone};
+use crate::prelude::*;
+use crate::tui::alert::Alerts;
+use crate::tui::app::{Counter, Flow};
+use crate::tui::idle::IdleWatch;
+use crate::tui::terminal::running_color;
+use crate::tui::ui::{show_pomo_ui, Frame, Line, PomoInfo};
+use crate::tui::warning::WarningCues;
+
+const CONFIRM_PROMPT: &str = "[Q]: Quit, [Enter/Y]: Yes, [Esc/N]: No";
+const INPUT_PROMPT: &str = "[Enter]: Confirm, [Esc]: Cancel";
+const IDLE_PROMPT: &str = "[Q]: Quit, [K]: Keep, [D]: Discard";
+const TOAST_TIME: Duration = Duration::from_secs(5);
+const HELP_PROMPT: &str = "[Q]: Quit, [Esc]: Close";
+
+/// A key that asks before doing anything.
+#[derive(Clone, Copy)]
+enum Confirm {
+    Skip,
+    Reset,
+}
+
+/// Text entry that temporarily takes over the pomodoro controls.
+enum Prompt {
+    Task,
+    Interruption(InterruptionKind, Duration),
+    Command,
+}
+
+/// Terminal front end for [`Pomodoro`].
+pub struct PomodoroApp {
+    pomodoro: Pomodoro,
+    extend: ExtendConfig,
+    /// Set by the pomodoro when a phase's time is up, cleared once the
+    /// alert played.
+    time_up: Rc<Cell<bool>>,
+    /// Set by the pomodoro when a phase starts, idle time counts from there.
+    phase_started: Rc<Cell<bool>>,
+    warnings: WarningCues,
+    /// Shared with the pomodoro, which hands it every finished phase.
+    history: Option<Rc<RefCell<HistoryWriter>>>,
+    idle: IdleWatch,
+    /// Set once work was paused for being idle this long.
+    away: Option<Duration>,
+    goal: GoalConfig,
+    goal_hook: Option<String>,
+    /// What was done on `day` before this run.
+    done_today: Progress,
+    day: NaiveDate,
+    goal_reached: bool,
+
+    confirm: Option<Confirm>,
+    confirm_reset: bool,
+    max_pause: Option<Duration>,
+    /// Set once the current pause went on for too long.
+    reminded: bool,
+    /// A note shown for a moment after something that can be undone.
+    toast: Option<(String, Instant)>,
+    input: Option<(Prompt, String)>,
+    /// The help overlay is shown instead of the pomodoro.
+    help: bool,
+    /// Why the last command failed.
+    error: Option<String>,
+
+    work_prompt: String,
+    pomo_prompt: String,
+    end_prompt: String,
+}
+
+impl PomodoroApp {
+    pub fn new(durations: Durations, task: Option<String>, config: &Config) -> Self {
+        let mut pomodoro = Pomodoro::new(durations)
+            .with_auto_start(config.auto_start.clone())
+            .with_task(task);
+        let time_up = Rc::new(Cell::new(false));
+        let phase_started = Rc::new(Cell::new(false));
+        let (up, started) = (Rc::clone(&time_up), Rc::clone(&phase_started));
+        pomodoro.subscribe(move |event: &Event| {
+            match event {
+                Event::TimeUp { .. } => up.set(true),
+                Event::PhaseStarted { .. } => started.set(true),
+                _ => {}
+            }
+            Ok(())
+        });
+
+        let extend = config.extend.clone();
+        let extend_keys = format!(
+            "[+/>]: +{}/+{}",
+            format_duration_short(extend.short),
+            format_duration_short(extend.long),
+        );
+        let work_prompt = format!(
+            "[Q]: Quit, [S]: Skip, [Space]: Pause/Resume, [R]: Reset, {extend_keys}, \
+            [T]: Task, [I/E]: Internal/External interruption, [?]: Help"
+        );
+        let pomo_prompt = format!(
+            "[Q]: Quit, [S]: Skip, [Space]: Pause/Resume, [R]: Reset, {extend_keys}, [T]: Task, \
+            [?]: Help"
+        );
+        let end_prompt = format!(
+            "[Q]: Quit, [Enter]: Next, [Space]: Pause/Resume, [R]: Reset, {extend_keys}, \
+            [T]: Task, [?]: Help"
+        );
+
+        Self {
+            pomodoro,
+            extend,
+            time_up,
+            phase_started,
+            warnings: WarningCues::default(),
+            history: None,
+            idle: IdleWatch::new(config.idle.clone()),
+            away: None,
+            goal: config.goal.clone(),
+            goal_hook: config.hooks.goal.clone(),
+            done_today: Progress::default(),
+            day: Local::now().date_naive(),
+            goal_reached: false,
+            confirm: None,
+            confirm_reset: config.confirm.reset,
+            toast: None,
+            max_pause: config.pause.max,
+            reminded: false,
+            input: None,
+            help: false,
+            error: None,
+            work_prompt,
+            pomo_prompt,
+            end_prompt,
+        }
+    }
+
+    pub fn with_warnings(mut self, warnings: WarningCues) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Reads the time from `time` instead of the system.
+    pub fn with_time(mut self, time: TimeSource) -> Self {
+        self.day = time.local().date_naive();
+        self.idle = self.idle.with_time(time.clone());
+        self.pomodoro = self.pomodoro.with_time(time);
+        self
+    }
+
+    /// Writes every finished phase to the history file at `path`.
+    pub fn with_history(mut self, path: PathBuf) -> Self {
+        let history = Rc::new(RefCell::new(HistoryWriter::new(path)));
+        let writer = Rc::clone(&history);
+        self.pomodoro
+            .subscribe(move |event: &Event| writer.borrow_mut().on_event(event));
+        self.history = Some(history);
+        self
+    }
+
+    /// Counts `progress` towards today's goal, the goal is only celebrated
+    /// if this run is what reaches it.
+    pub fn with_done_today(mut self, progress: Progress) -> Self {
+        self.done_today = progress;
+        self.goal_reached = self.goal.is_met(&progress);
+        self
+    }
+
+    /// Progress on the day a phase started, so work started before midnight
+    /// counts for the day before.
+    fn today(&self) -> Progress {
+        let mut progress = self.done_today;
+        let records = self.pomodoro.records();
+        for record in records.iter().filter(|r| r.start.date_naive() == self.day) {
+            progress.add(record);
+        }
+        progress
+    }
+
+    /// Records the unfinished phase and returns every phase of this run,
+    /// after one last try at saving what couldn't be saved before.
+    pub fn finish(self) -> Result<Vec<PhaseRecord>> {
+        let records = self.pomodoro.finish()?;
+        if let Some(history) = &self.history {
+            history
+                .borrow_mut()
+                .flush()
+                .context("Failed to save the history")?;
+        }
+        Ok(records)
+    }
+
+    /// Pauses work that went on without the user and asks whether to keep
+    /// the time. Returns false if there was nothing to pause.
+    fn went_away(&mut self, idle: Duration) -> Result<bool> {
+        let pomodoro = &mut self.pomodoro;
+        if self.away.is_some()
+            || pomodoro.mode() != Mode::Work
+            || !pomodoro.is_running()
+            || pomodoro.is_time_up()
+        {
+            return Ok(false);
+        }
+        pomodoro.toggle()?;
+        self.away = Some(idle.min(pomodoro.elapsed()));
+        Ok(true)
+    }
+
+    fn run_command(&mut self, command: Command) -> Result<()> {
+        match command {
+            Command::Extend(amount) => self.pomodoro.extend(amount),
+            Command::Task(task) => self.pomodoro.set_task(task),
+            Command::Set(mode, duration) => {
+                let mode = mode.unwrap_or(self.pomodoro.mode());
+                self.pomodoro.set_duration(mode, duration)
+            }
+            Command::Goto(mode) => self.pomodoro.goto(mode),
+            Command::Back => self.back(),
+        }
+    }
+
+    /// E.g. `Paused 3m 12s, 2 pauses, 4m 0s in total`, red once it went on
+    /// for too long.
+    fn paused_line(&self) -> Option<Line> {
+        let pomodoro = &self.pomodoro;
+        let pause = pomodoro.current_pause()?;
+        let mut text = format!("Paused {}", format_duration_short(pause));
+        if pomodoro.pauses() > 1 {
+            text += &format!(
+                ", {} pauses, {} in total",
+                pomodoro.pauses(),
+                format_duration_short(pomodoro.paused()),
+            );
+        }
+        let color = match self.max_pause {
+            Some(max) if pause >= max => Color::Red,
+            _ => Color::Yellow,
+        };
+        Some(text.with(color).into())
+    }
+
+    fn toast(&mut self, text: &str) {
+        self.toast = Some((text.to_string(), self.pomodoro.time().now()));
+    }
+
+    fn reset(&mut self) {
+        self.pomodoro.reset();
+        self.toast("Reset — press u to undo");
+    }
+
+    fn skip(&mut self) -> Result<()> {
+        self.pomodoro.skip()?;
+        self.toast("Skipped — press u to undo");
+        Ok(())
+    }
+
+    fn undo(&mut self) -> Result<()> {
+        match self.pomodoro.undo()? {
+            Some(Undone::Reset) => self.toast("Reset undone"),
+            Some(Undone::PhaseChange) => self.toast("Back to the previous phase"),
+            None => self.error = Some("Nothing to undo".to_string()),
+        }
+        Ok(())
+    }
+
+    fn back(&mut self) -> Result<()> {
+        if !self.pomodoro.back()? {
+            self.error = Some("This is the first phase".to_string());
+        }
+        Ok(())
+    }
+
+    /// Lengthens or shortens this kind of phase by the short extend amount,
+    /// keeping at least that much.
+    fn adjust(&mut self, longer: bool) -> Result<()> {
+        let mode = self.pomodoro.mode();
+        let duration = self.pomodoro.durations().of(mode);
+        let step = self.extend.short;
+        let duration = match longer {
+            true => duration + step,
+            false => duration.saturating_sub(step).max(step),
+        };
+        self.pomodoro.set_duration(mode, duration)
+    }
+
+    /// Every key that does something right now, and what it does.
+    fn bindings(&self) -> Vec<(&'static str, String)> {
+        let pomodoro = &self.pomodoro;
+        let name = match pomodoro.mode() {
+            Mode::Work => "work phase",
+            Mode::Break => "break",
+            Mode::LongBreak => "long break",
+        };
+        let step = format_duration_short(self.extend.short);
+        let mut bindings = vec![("Space", "Pause or resume".to_string())];
+        match pomodoro.is_time_up() {
+            true => bindings.push(("Enter", "Start the next phase".to_string())),
+            false => bindings.push(("S", "Skip this phase, asks first".to_string())),
+        }
+        bindings.extend([
+            ("R", "Restart this phase".to_string()),
+            (
+                "+",
+                format!("Add {}", format_duration_short(self.extend.short)),
+            ),
+            (
+                ">",
+                format!("Add {}", format_duration_short(self.extend.long)),
+            ),
+            ("[", format!("Shorten every {name} by {step}")),
+            ("]", format!("Lengthen every {name} by {step}")),
+            ("T", "Set the task".to_string()),
+            ("P", "Go back to the previous phase".to_string()),
+            ("U", "Undo the last reset, skip or phase change".to_string()),
+        ]);
+        if pomodoro.mode() != Mode::LongBreak {
+            bindings.push(("L", "Take a long break now".to_string()));
+        }
+        if pomodoro.mode() == Mode::Work {
+            bindings.extend([
+                ("I", "Note an internal interruption".to_string()),
+                ("E", "Note an external interruption".to_string()),
+            ]);
+        }
+        bindings.extend([
+            (":", "Run a command".to_string()),
+            ("?", "Show this help".to_string()),
+            ("Q", "Quit".to_string()),
+        ]);
+        bindings
+    }
+
+    fn render_help(&self, frame: &mut Frame) {
+        let columns = |rows: &[(&str, String)]| {
+            let width = rows.iter().map(|(left, _)| left.len()).max().unwrap_or(0);
+            let rows = rows.iter();
+            rows.map(|(left, right)| format!("{left:width$}  {right}"))
+                .collect::<Vec<_>>()
+        };
+        let commands: Vec<_> = USAGE
+            .iter()
+            .map(|(usage, description)| (*usage, description.to_string()))
+            .collect();
+        let mut rows = columns(&self.bindings());
+        rows.push(String::new());
+        rows.extend(columns(&commands));
+
+        frame.push("Help".with(Color::Blue));
+        // Padded to the same width so the columns stay aligned when centered.
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        for row in rows {
+            frame.push(format!("{row:width$}"));
+        }
+        frame.push(HELP_PROMPT);
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) -> Result<()> {
+        let Some((prompt, text)) = self.input.as_mut() else {
+            return Ok(());
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let text = text.trim();
+                let text = (!text.is_empty()).then(|| text.to_string());
+                match *prompt {
+                    Prompt::Task => self.pomodoro.set_task(text)?,
+                    Prompt::Interruption(kind, at) => self.pomodoro.interrupt(Interruption {
+                        kind,
+                        at,
+                        note: text,
+                    })?,
+                    Prompt::Command => match parse_command(text.as_deref().unwrap_or_default()) {
+                        Ok(command) => self.run_command(command)?,
+                        Err(e) => self.error = Some(e.to_string()),
+                    },
+                }
+                self.input = None;
+            }
+            KeyCode::Esc => self.input = None,
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) => text.push(c),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl Counter for PomodoroApp {
+    fn tick(&mut self, alerts: &mut Alerts) -> Result<()> {
+        self.pomodoro.tick()?;
+        if let Some(error) = self
+            .history
+            .as_ref()
+            .and_then(|h| h.borrow_mut().take_error())
+        {
+            self.error = Some(error);
+        }
+        let label = match self.pomodoro.mode() {
+            Mode::Work => "work",
+            Mode::Break => "break",
+            Mode::LongBreak => "long break",
+        };
+        self.warnings.tick(
+            alerts,
+            self.pomodoro.phase(),
+            label,
+            self.pomodoro.remaining(),
+            self.pomodoro.target(),
+        );
+        if let Some(error) = self.warnings.take_error() {
+            self.error = Some(error);
+        }
+
+        if self.phase_started.replace(false) {
+            self.idle.activity();
+        }
+        if let Some(idle) = self.idle.away() {
+            self.went_away(idle)?;
+        }
+        if let Some(error) = self.idle.take_error() {
+            self.error = Some(error);
+        }
+        match self.pomodoro.current_pause() {
+            Some(pause) if !self.reminded && self.max_pause.is_some_and(|max| pause >= max) => {
+                self.reminded = true;
+                alerts.play();
+            }
+            Some(_) => {}
+            None => self.reminded = false,
+        }
+
+        let day = self.pomodoro.time().local().date_naive();
+        if day != self.day {
+            self.day = day;
+            self.done_today = Progress::default();
+            self.goal_reached = false;
+        }
+        let today = self.today();
+        if !self.goal_reached && self.goal.is_met(&today) {
+            self.goal_reached = true;
+            alerts.play();
+            if let Some(command) = &self.goal_hook {
+                let env = [
+                    ("PORSMO_POMODOROS", today.pomodoros.to_string()),
+                    ("PORSMO_FOCUS", format_duration_short(today.focus)),
+                ];
+                if let Err(e) = hooks::run(command, "goal", &env) {
+                    self.error = Some(format!("{e:#}"));
+                }
+            }
+        }
+        if self.time_up.replace(false) {
+            alerts.play();
+        }
+        Ok(())
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let pomodoro = &self.pomodoro;
+        let color = running_color(pomodoro.is_running());
+        let session = pomodoro.session();
+        let info = PomoInfo {
+            session,
+            task: pomodoro.task(),
+            interruptions: pomodoro.interruptions(),
+            today: self.goal.describe(&self.today()),
+            paused: self.paused_line(),
+        };
+        let time_up = pomodoro.is_time_up();
+
+        let auto_note = match pomodoro.auto_start_in() {
+            Some(starts_in) => format!(" Next phase in {}", format_duration_short(starts_in)),
+            None => String::new(),
+        };
+        let time_left =
+            format_duration(pomodoro.remaining()).with(self.warnings.color(pomodoro.is_running()));
+        let excess_time = format!("+{}", format_duration(pomodoro.overtime())).with(color);
+
+        let (icon, name) = match pomodoro.mode() {
+            Mode::Work => ("🍅", "Work"),
+            Mode::Break => ("☕", "Break"),
+            Mode::LongBreak => ("☕", "Long break"),
+        };
+        let clock = match time_up {
+            true => format!("+{}", format_clock(pomodoro.overtime())),
+            false => format_clock(pomodoro.remaining()),
+        };
+        frame.set_title(format!("{icon} {clock} {name} #{session}"));
+        frame.set_progress(pomodoro.elapsed(), pomodoro.target());
+        if self.help {
+            return self.render_help(frame);
+        }
+
+        match pomodoro.mode() {
+            _ if self.away.is_some() => show_pomo_ui(
+                frame,
+                format!(
+                    "Away for {}, keep that time?",
+                    format_duration_short(self.away.unwrap_or_default())
+                )
+                .with(Color::Red),
+                time_left,
+                IDLE_PROMPT,
+                &info,
+            ),
+
+            _ if self.input.is_some() => {
+                let title = match &self.input {
+                    Some((Prompt::Task, text)) => format!("Task: {text}_"),
+                    Some((Prompt::Command, text)) => format!(":{text}_"),
+                    Some((Prompt::Interruption(kind, _), text)) => {
+                        format!("{kind} interruption, note (optional): {text}_")
+                    }
+                    None => unreachable!(),
+                };
+                show_pomo_ui(frame, title, time_left, INPUT_PROMPT, &info)
+            }
+
+            _ if self.confirm.is_some() => {
+                let question = match self.confirm {
+                    Some(Confirm::Skip) => "Skip this session?",
+                    _ => "Reset this session?",
+                };
+                show_pomo_ui(
+                    frame,
+                    question.with(Color::Red),
+                    time_left,
+                    CONFIRM_PROMPT,
+                    &info,
+                )
+            }
+
+            Mode::Work if !time_up => show_pomo_ui(
+                frame,
+                "Pomodoro (Work)",
+                time_left,
+                &self.work_prompt,
+                &info,
+            ),
+
+            Mode::Work => show_pomo_ui(
+                frame,
+                format!("Time for a break!{auto_note}").with(Color::Red),
+                excess_time,
+                &self.end_prompt,
+                &info,
+            ),
+
+            Mode::Break if !time_up => show_pomo_ui(
+                frame,
+                "Enjoy your break!".with(Color::Blue),
+                time_left,
+                &self.pomo_prompt,
+                &info,
+            ),
+
+            Mode::LongBreak if !time_up => show_pomo_ui(
+                frame,
+                "Give your mind some rest!".with(Color::Blue),
+                time_left,
+                &self.pomo_prompt,
+                &info,
+            ),
+
+            Mode::Break | Mode::LongBreak => show_pomo_ui(
+                frame,
+                format!("Time to start working!{auto_note}").with(Color::Red),
+                excess_time,
+                &self.end_prompt,
+                &info,
+            ),
+        }
+        if let Some(error) = &self.error {
+            frame.push(error.as_str().with(Color::Red));
+        }
+        if let Some((toast, since)) = &self.toast {
+            if pomodoro.time().now() - *since < TOAST_TIME {
+                frame.push(toast.as_str().with(Color::Yellow));
+            }
+        }
+    }
+
+    fn handle_action(&mut self, key: KeyEvent) -> Result<Flow> {
+        // The key that brings the user back only answers the prompt.
+        if let Some(idle) = self.idle.activity() {
+            if self.went_away(idle)? {
+                return Ok(Flow::Continue);
+            }
+        }
+        if let Some(idle) = self.away {
+            match key.code {
+                KeyCode::Char('q') => return Ok(Flow::Quit),
+                KeyCode::Char('k') => {
+                    self.away = None;
+                    self.pomodoro.toggle()?;
+                }
+                KeyCode::Char('d') => {
+                    self.away = None;
+                    self.pomodoro.discard(idle);
+                    self.pomodoro.toggle()?;
+                }
+                _ => {}
+            }
+            return Ok(Flow::Continue);
+        }
+
+        if self.input.is_some() {
+            self.handle_input(key)?;
+            return Ok(Flow::Continue);
+        }
+
+        if self.help {
+            match key.code {
+                KeyCode::Char('q') => return Ok(Flow::Quit),
+                _ => self.help = false,
+            }
+            return Ok(Flow::Continue);
+        }
+
+        let elapsed = self.pomodoro.elapsed();
+        let time_up = self.pomodoro.is_time_up();
+
+        if let Some(confirm) = self.confirm {
+            match key.code {
+                KeyCode::Char('q') => return Ok(Flow::Quit),
+                KeyCode::Enter | KeyCode::Char('y') => {
+                    self.confirm = None;
+                    match confirm {
+                        Confirm::Skip => self.skip()?,
+                        Confirm::Reset => self.reset(),
+                    }
+                }
+                KeyCode::Esc | KeyCode::Char('n') => self.confirm = None,
+                _ => {}
+            }
+            return Ok(Flow::Continue);
+        }
+
+        self.error = None;
+        match key.code {
+            KeyCode::Char('q') => return Ok(Flow::Quit),
+            KeyCode::Char('s') if !time_up => self.confirm = Some(Confirm::Skip),
+            KeyCode::Enter if time_up => self.pomodoro.advance()?,
+            KeyCode::Char(' ') => self.pomodoro.toggle()?,
+            KeyCode::Char('r') if self.confirm_reset => self.confirm = Some(Confirm::Reset),
+            KeyCode::Char('r') => self.reset(),
+            KeyCode::Char('u') => self.undo()?,
+            KeyCode::Char('+') => self.pomodoro.extend(self.extend.short)?,
+            KeyCode::Char('>') => self.pomodoro.extend(self.extend.long)?,
+            KeyCode::Char('[') => self.adjust(false)?,
+            KeyCode::Char(']') => self.adjust(true)?,
+            KeyCode::Char('l') if self.pomodoro.mode() != Mode::LongBreak => {
+                self.pomodoro.goto(Mode::LongBreak)?;
+                self.toast("Long break — press u to undo");
+            }
+            KeyCode::Char('p') => self.back()?,
+            KeyCode::Char('t') => {
+                let task = self.pomodoro.task().unwrap_or_default().to_string();
+                self.input = Some((Prompt::Task, task));
+            }
+            KeyCode::Char(':') => {
+                self.input = Some((Prompt::Command, String::new()));
+            }
+            KeyCode::Char('?') => self.help = true,
+            KeyCode::Char('i') if self.pomodoro.mode() == Mode::Work => {
+                let prompt = Prompt::Interruption(InterruptionKind::Internal, elapsed);
+                self.input = Some((prompt, String::new()));
+            }
+            KeyCode::Char('e') if self.pomodoro.mode() == Mode::Work => {
+                let prompt = Prompt::Interruption(InterruptionKind::External, elapsed);
+                self.input = Some((prompt, String::new()));
+            }
+            _ => {}
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn handle_focus(&mut self, _focused: bool) -> Result<()> {
+        if let Some(idle) = self.idle.activity() {
+            self.went_away(idle)?;
+        }
+        Ok(())
+    }
+}
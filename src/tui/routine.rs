@@ -0,0 +1,148 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::style::{Color, Stylize};
+
+use crate::clock::TimeSource;
+use crate::format::{format_clock, format_duration};
+use crate::prelude::*;
+use crate::routine::{Repeat, Routine, RoutineConfig};
+use crate::tui::alert::Alerts;
+use crate::tui::app::{Counter, Flow};
+use crate::tui::ui::{show_ui, Frame};
+use crate::tui::warning::WarningCues;
+
+const CONTROLS: &str = "[Q]: quit, [Space]: pause/resume, [R]: Reset, [Enter]: Next";
+
+pub struct RoutineApp {
+    name: String,
+    routine: Routine,
+    colors: Vec<Option<Color>>,
+    warnings: WarningCues,
+    /// Why the warning hook failed, until the next key.
+    error: Option<String>,
+}
+
+impl RoutineApp {
+    pub fn new(name: String, config: RoutineConfig) -> Result<Self> {
+        let routine = Routine::new(config)?;
+        let colors = routine
+            .phases()
+            .iter()
+            .map(|phase| match &phase.color {
+                Some(color) => Color::try_from(color.as_str())
+                    .map(Some)
+                    .map_err(|_| anyhow!("Unknown color {color:?} for phase {:?}", phase.name)),
+                None => Ok(None),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            name,
+            routine,
+            colors,
+            warnings: WarningCues::default(),
+            error: None,
+        })
+    }
+
+    pub fn with_warnings(mut self, warnings: WarningCues) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Reads the time from `time` instead of the system.
+    pub fn with_time(mut self, time: TimeSource) -> Self {
+        self.routine = self.routine.with_time(time);
+        self
+    }
+}
+
+impl Counter for RoutineApp {
+    fn tick(&mut self, alerts: &mut Alerts) -> Result<()> {
+        if !self.routine.is_finished() {
+            let routine = &self.routine;
+            // Every phase of every round warns on its own.
+            let phase = (routine.round() as usize - 1) * routine.phases().len() + routine.index();
+            self.warnings.tick(
+                alerts,
+                phase,
+                &routine.phase().name,
+                routine.remaining(),
+                routine.phase().duration,
+            );
+            if let Some(error) = self.warnings.take_error() {
+                self.error = Some(error);
+            }
+        }
+        if let Some(phase) = self.routine.tick() {
+            alerts.sound(phase.sound);
+        }
+        Ok(())
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        if self.routine.is_finished() {
+            frame.set_title(format!("🏁 Routine {} complete", self.name));
+            show_ui(
+                frame,
+                format!("Routine {} complete!", self.name),
+                "",
+                "[Q]: quit",
+            );
+            return;
+        }
+
+        let phase = self.routine.phase();
+        let clock = match self.routine.is_time_up() {
+            true => format!("+{}", format_clock(self.routine.overtime())),
+            false => format_clock(self.routine.remaining()),
+        };
+        frame.set_title(format!("🔁 {clock} {}", phase.name));
+        frame.set_progress(
+            phase.duration.saturating_sub(self.routine.remaining()),
+            phase.duration,
+        );
+        let color = self.colors[self.routine.index()];
+        let title = match self.routine.next_phase() {
+            _ if !self.routine.is_time_up() => phase.name.clone(),
+            Some(next) => format!("{} is over, next: {}", phase.name, next.name),
+            None => format!("{} is over, that was the last phase", phase.name),
+        };
+        let title = match color {
+            Some(color) => title.with(color),
+            None => title.stylize(),
+        };
+        let clock = if self.routine.is_time_up() {
+            format!("+{}", format_duration(self.routine.overtime())).with(Color::Red)
+        } else {
+            format_duration(self.routine.remaining())
+                .with(self.warnings.color(self.routine.is_running()))
+        };
+        show_ui(frame, title, clock, CONTROLS);
+
+        frame.push(format!(
+            "Phase: {}/{}",
+            self.routine.index() + 1,
+            self.routine.phases().len()
+        ));
+        match self.routine.repeat() {
+            Repeat::Times(1) => {}
+            Repeat::Times(times) => frame.push(format!("Round: {}/{times}", self.routine.round())),
+            Repeat::Forever => frame.push(format!("Round: {}", self.routine.round())),
+        }
+        if let Some(error) = &self.error {
+            frame.push(error.as_str().with(Color::Red));
+        }
+    }
+
+    fn handle_action(&mut self, key: KeyEvent) -> Result<Flow> {
+        self.error = None;
+        match key.code {
+            KeyCode::Char('q') => return Ok(Flow::Quit),
+            _ if self.routine.is_finished() => {}
+            KeyCode::Char(' ') => self.routine.toggle(),
+            KeyCode::Char('r') => self.routine.reset(),
+            KeyCode::Enter => self.routine.advance(),
+            _ => {}
+        }
+        Ok(Flow::Continue)
+    }
+}
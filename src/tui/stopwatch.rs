@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::style::Stylize;
+
+use crate::clock::{Clock, TimeSource};
+use crate::format::{format_clock, format_duration};
+use crate::prelude::*;
+use crate::tui::alert::Alerts;
+use crate::tui::app::{Counter, Flow};
+use crate::tui::terminal::running_color;
+use crate::tui::ui::{show_ui, Frame};
+
+#[derive(Default)]
+pub struct Stopwatch {
+    clock: Clock,
+}
+
+impl Stopwatch {
+    /// Reads the time from `time` instead of the system.
+    pub fn with_time(mut self, time: TimeSource) -> Self {
+        self.clock = Clock::new(time);
+        self
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+}
+
+impl Counter for Stopwatch {
+    fn tick(&mut self, _alerts: &mut Alerts) -> Result<()> {
+        Ok(())
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let color = running_color(self.clock.is_running());
+        frame.set_title(format!(
+            "⏱ {} Stopwatch",
+            format_clock(self.clock.elapsed())
+        ));
+        show_ui(
+            frame,
+            "Stopwatch",
+            format_duration(self.clock.elapsed()).with(color),
+            "[Q]: quit, [Space]: Pause/Resume",
+        )
+    }
+
+    fn handle_action(&mut self, key: KeyEvent) -> Result<Flow> {
+        match key.code {
+            KeyCode::Char('q') => return Ok(Flow::Quit),
+            KeyCode::Char(' ') => self.clock.toggle(),
+            _ => {}
+        }
+        Ok(Flow::Continue)
+    }
+}
@@ -0,0 +1,141 @@
+use crate::config::TerminalConfig;
+use crate::prelude::*;
+use crate::tui::layout::{button_at, lay_out, Placed};
+use crate::tui::ui::{Frame, Screen};
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{
+        DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, KeyCode,
+    },
+    execute, queue,
+    style::{Color, Print, Stylize},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, size, Clear, ClearType, EnterAlternateScreen,
+        LeaveAlternateScreen, SetTitle,
+    },
+};
+use std::io::{stdout, Stdout, Write};
+
+/// Saves the window title so it can be restored, an xterm extension that
+/// most terminals support.
+const PUSH_TITLE: &str = "\x1b[22;0t";
+const POP_TITLE: &str = "\x1b[23;0t";
+const CLEAR_PROGRESS: &str = "\x1b]9;4;0\x07";
+
+pub struct TerminalHandler {
+    stdout: Stdout,
+    config: TerminalConfig,
+    title: Option<String>,
+    progress: Option<u8>,
+    /// Columns and rows, kept up to date by resize events.
+    size: (u16, u16),
+    /// Where the last frame's lines went, to find what was clicked.
+    placed: Vec<Placed>,
+}
+
+impl TerminalHandler {
+    pub fn new(config: &TerminalConfig) -> Result<Self> {
+        let size = size().context("Failed to get the terminal size!")?;
+        enable_raw_mode().context("Failed to enter raw mode!")?;
+        // Restores the terminal on drop if anything below fails.
+        let mut handler = Self {
+            stdout: std::io::stdout(),
+            config: config.clone(),
+            title: None,
+            progress: None,
+            size,
+            placed: Vec::new(),
+        };
+
+        let stdout = &mut handler.stdout;
+        execute!(
+            stdout,
+            EnterAlternateScreen,
+            EnableFocusChange,
+            Hide,
+            Clear(ClearType::All),
+            MoveTo(0, 0),
+        )
+        .context("Failed to initialize the terminal!")?;
+        if config.title {
+            execute!(stdout, Print(PUSH_TITLE))?;
+        }
+        if config.mouse {
+            execute!(stdout, EnableMouseCapture)?;
+        }
+        Ok(handler)
+    }
+
+    pub fn stdout(&mut self) -> &mut Stdout {
+        &mut self.stdout
+    }
+}
+
+impl Screen for TerminalHandler {
+    fn draw(&mut self, frame: &Frame) -> Result<()> {
+        let output = &mut self.stdout;
+        if self.config.title && frame.title.is_some() && frame.title != self.title {
+            self.title = frame.title.clone();
+            queue!(output, SetTitle(frame.title.as_deref().unwrap_or_default()))?;
+        }
+        if self.config.progress && frame.progress.is_some() && frame.progress != self.progress {
+            self.progress = frame.progress;
+            let percent = frame.progress.unwrap_or_default();
+            queue!(output, Print(format!("\x1b]9;4;1;{percent}\x07")))?;
+        }
+        let (width, height) = self.size;
+        self.placed = lay_out(&frame.lines, width, height);
+        for y in 0..height {
+            queue!(output, MoveTo(0, y), Clear(ClearType::CurrentLine))?;
+            let Some(placed) = self.placed.iter().find(|placed| placed.y == y) else {
+                continue;
+            };
+            let line = &placed.line;
+            queue!(output, MoveTo(placed.x, y))?;
+            match line.color {
+                Some(color) => queue!(output, Print(line.text.as_str().with(color)))?,
+                None => queue!(output, Print(&line.text))?,
+            }
+        }
+        output.flush()?;
+        Ok(())
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.size = (width, height);
+    }
+
+    fn button_at(&self, column: u16, row: u16) -> Option<KeyCode> {
+        button_at(&self.placed, column, row)
+    }
+}
+
+impl Drop for TerminalHandler {
+    fn drop(&mut self) {
+        disable_raw_mode().expect("Failed to disable raw mode");
+        if self.config.title {
+            execute!(stdout(), Print(POP_TITLE)).expect("Failed to restore the title");
+        }
+        if self.config.mouse {
+            execute!(stdout(), DisableMouseCapture).expect("Failed to release the mouse");
+        }
+        if self.progress.is_some() {
+            execute!(stdout(), Print(CLEAR_PROGRESS)).expect("Failed to clear the progress");
+        }
+        execute!(
+            stdout(),
+            Clear(ClearType::All),
+            Show,
+            DisableFocusChange,
+            LeaveAlternateScreen,
+        )
+        .expect("Failed to reset screen");
+    }
+}
+
+pub fn running_color(running: bool) -> Color {
+    match running {
+        true => Color::Green,
+        false => Color::Red,
+    }
+}
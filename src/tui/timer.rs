@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::style::{Color, Stylize};
+
+use crate::clock::{Clock, Extension, TimeSource};
+use crate::config::ExtendConfig;
+use crate::format::{format_clock, format_duration, format_duration_short};
+use crate::prelude::*;
+use crate::routine::Repeat;
+use crate::tui::alert::Alerts;
+use crate::tui::app::{Counter, Flow};
+use crate::tui::terminal::running_color;
+use crate::tui::ui::{show_ui, Frame};
+use crate::tui::warning::WarningCues;
+
+pub struct Timer {
+    target: Duration,
+    extend: ExtendConfig,
+    extension: Extension,
+    clock: Clock,
+    alerted: bool,
+    repeat: Repeat,
+    iteration: u32,
+    /// Time spent in the iterations before this one.
+    past: Duration,
+    warnings: WarningCues,
+    controls: String,
+    /// Why the warning hook failed, until the next key.
+    error: Option<String>,
+}
+
+impl Timer {
+    pub fn new(target: Duration, extend: ExtendConfig) -> Self {
+        let controls = format!(
+            "[Q]: quit, [Space]: pause/resume, [R]: Reset, [+/>]: +{}/+{}",
+            format_duration_short(extend.short),
+            format_duration_short(extend.long),
+        );
+        Self {
+            target,
+            extend,
+            extension: Extension::default(),
+            clock: Clock::default(),
+            alerted: false,
+            repeat: Repeat::default(),
+            iteration: 1,
+            past: Duration::ZERO,
+            warnings: WarningCues::default(),
+            controls,
+            error: None,
+        }
+    }
+
+    /// Restarts the timer after every alert until it ran `repeat` times.
+    pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn with_warnings(mut self, warnings: WarningCues) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Reads the time from `time` instead of the system.
+    pub fn with_time(mut self, time: TimeSource) -> Self {
+        self.clock = Clock::new(time);
+        self
+    }
+
+    /// Counted from 1.
+    pub fn iteration(&self) -> u32 {
+        self.iteration
+    }
+
+    /// Time spent across all iterations.
+    pub fn total_elapsed(&self) -> Duration {
+        self.past + self.clock.elapsed()
+    }
+
+    fn target(&self) -> Duration {
+        self.extension.target(self.target)
+    }
+
+    /// Adds `amount` on top of whatever time is left, an ended timer gets
+    /// `amount` from now.
+    fn extend(&mut self, amount: Duration) {
+        self.extension
+            .add(self.target, self.clock.elapsed(), amount);
+        self.alerted = false;
+    }
+}
+
+impl Counter for Timer {
+    fn tick(&mut self, alerts: &mut Alerts) -> Result<()> {
+        let remaining = self.target().saturating_sub(self.clock.elapsed());
+        self.warnings.tick(
+            alerts,
+            self.iteration as usize,
+            "timer",
+            remaining,
+            self.target(),
+        );
+        if let Some(error) = self.warnings.take_error() {
+            self.error = Some(error);
+        }
+        if self.clock.elapsed() >= self.target() && !self.alerted {
+            self.alerted = true;
+            alerts.play();
+            if self.repeat.continues_after(self.iteration) {
+                self.iteration += 1;
+                self.past += self.clock.elapsed();
+                self.clock.reset();
+                self.extension = Extension::default();
+                self.alerted = false;
+            }
+        }
+        Ok(())
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let elapsed = self.clock.elapsed();
+        let target = self.target();
+        let color = running_color(self.clock.is_running());
+        frame.set_progress(elapsed, target);
+        if elapsed >= target {
+            frame.set_title(format!("⏳ +{} Timer", format_clock(elapsed - target)));
+            let excess_time = format_duration(elapsed.saturating_sub(target));
+            show_ui(
+                frame,
+                "Timer has ended".with(Color::Red),
+                format!("+{excess_time}").with(color),
+                &self.controls,
+            );
+        } else {
+            let time_left = target.saturating_sub(elapsed);
+            frame.set_title(format!("⏳ {} Timer", format_clock(time_left)));
+            let color = self.warnings.color(self.clock.is_running());
+            show_ui(
+                frame,
+                "Timer",
+                format_duration(time_left).with(color),
+                &self.controls,
+            );
+        }
+
+        let iteration = match self.repeat {
+            Repeat::Times(1) => None,
+            Repeat::Times(times) => Some(format!("Iteration: {}/{times}", self.iteration)),
+            Repeat::Forever => Some(format!("Iteration: {}", self.iteration)),
+        };
+        if let Some(iteration) = iteration {
+            frame.push(iteration);
+            frame.push(format!("Total: {}", format_duration(self.total_elapsed())));
+        }
+        if let Some(error) = &self.error {
+            frame.push(error.as_str().with(Color::Red));
+        }
+    }
+
+    fn handle_action(&mut self, key: KeyEvent) -> Result<Flow> {
+        self.error = None;
+        match key.code {
+            KeyCode::Char('q') => return Ok(Flow::Quit),
+            KeyCode::Char(' ') => self.clock.toggle(),
+            KeyCode::Char('r') => {
+                self.clock.reset();
+                self.extension = Extension::default();
+                self.alerted = false;
+            }
+            KeyCode::Char('+') => self.extend(self.extend.short),
+            KeyCode::Char('>') => self.extend(self.extend.long),
+            _ => {}
+        }
+        Ok(Flow::Continue)
+    }
+}
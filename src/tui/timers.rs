@@ -0,0 +1,245 @@
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::style::{Color, Stylize};
+
+use crate::clock::{Clock, Extension, TimeSource};
+use crate::config::ExtendConfig;
+use crate::format::{format_clock, format_duration, format_duration_short, parse_named_timer};
+use crate::prelude::*;
+use crate::tui::alert::Alerts;
+use crate::tui::app::{Counter, Flow};
+use crate::tui::ui::Frame;
+use crate::tui::warning::WarningCues;
+
+const INPUT_PROMPT: &str = "[Enter]: Add, [Esc]: Cancel";
+
+struct NamedTimer {
+    name: String,
+    target: Duration,
+    extension: Extension,
+    clock: Clock,
+    alerted: bool,
+    warnings: WarningCues,
+}
+
+impl NamedTimer {
+    fn target(&self) -> Duration {
+        self.extension.target(self.target)
+    }
+
+    fn has_ended(&self) -> bool {
+        self.clock.elapsed() >= self.target()
+    }
+}
+
+/// Several named timers on one screen, each with its own clock and alert.
+pub struct Timers {
+    timers: Vec<NamedTimer>,
+    extend: ExtendConfig,
+    time: TimeSource,
+    /// Copied into every timer, each one warns on its own.
+    warnings: WarningCues,
+    selected: usize,
+    created: usize,
+    input: Option<String>,
+    error: Option<String>,
+    controls: String,
+}
+
+impl Timers {
+    pub fn new(timers: Vec<(Option<String>, Duration)>, extend: ExtendConfig) -> Self {
+        let controls = format!(
+            "[Q]: Quit, [Up/Down]: Select, [Space]: Pause/Resume, [R]: Reset, \
+            [+/>]: +{}/+{}, [A]: Add, [D]: Delete",
+            format_duration_short(extend.short),
+            format_duration_short(extend.long),
+        );
+        let mut this = Self {
+            timers: Vec::new(),
+            extend,
+            time: TimeSource::default(),
+            warnings: WarningCues::default(),
+            selected: 0,
+            created: 0,
+            input: None,
+            error: None,
+            controls,
+        };
+        for (name, target) in timers {
+            this.add(name, target);
+        }
+        this
+    }
+
+    pub fn with_warnings(mut self, warnings: WarningCues) -> Self {
+        for timer in &mut self.timers {
+            timer.warnings = warnings.clone();
+        }
+        self.warnings = warnings;
+        self
+    }
+
+    /// Reads the time from `time` instead of the system.
+    pub fn with_time(mut self, time: TimeSource) -> Self {
+        for timer in &mut self.timers {
+            timer.clock = Clock::new(time.clone());
+        }
+        self.time = time;
+        self
+    }
+
+    fn add(&mut self, name: Option<String>, target: Duration) {
+        self.created += 1;
+        let name = name.unwrap_or_else(|| format!("Timer {}", self.created));
+        self.timers.push(NamedTimer {
+            name,
+            target,
+            extension: Extension::default(),
+            clock: Clock::new(self.time.clone()),
+            alerted: false,
+            warnings: self.warnings.clone(),
+        });
+    }
+
+    fn selected(&mut self) -> Option<&mut NamedTimer> {
+        self.timers.get_mut(self.selected)
+    }
+
+    fn extend(&mut self, amount: Duration) {
+        if let Some(timer) = self.selected() {
+            timer
+                .extension
+                .add(timer.target, timer.clock.elapsed(), amount);
+            timer.alerted = false;
+        }
+    }
+
+    fn handle_input(&mut self, key: KeyEvent) {
+        let Some(text) = self.input.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                match parse_named_timer(text) {
+                    Ok((name, target)) => {
+                        self.add(name, target);
+                        self.selected = self.timers.len() - 1;
+                        self.error = None;
+                    }
+                    Err(e) => self.error = Some(e.to_string()),
+                }
+                self.input = None;
+            }
+            KeyCode::Esc => self.input = None,
+            KeyCode::Backspace => {
+                text.pop();
+            }
+            KeyCode::Char(c) => text.push(c),
+            _ => {}
+        }
+    }
+}
+
+impl Counter for Timers {
+    fn tick(&mut self, alerts: &mut Alerts) -> Result<()> {
+        for timer in &mut self.timers {
+            let remaining = timer.target().saturating_sub(timer.clock.elapsed());
+            timer
+                .warnings
+                .tick(alerts, 1, &timer.name, remaining, timer.target());
+            if let Some(error) = timer.warnings.take_error() {
+                self.error = Some(error);
+            }
+            if timer.has_ended() && !timer.alerted {
+                timer.alerted = true;
+                alerts.play();
+            }
+        }
+        Ok(())
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let next = self
+            .timers
+            .iter()
+            .filter(|timer| !timer.has_ended())
+            .min_by_key(|timer| timer.target().saturating_sub(timer.clock.elapsed()));
+        if let Some(timer) = next {
+            let time_left = timer.target().saturating_sub(timer.clock.elapsed());
+            frame.set_title(format!("⏳ {} {}", format_clock(time_left), timer.name));
+        }
+
+        match &self.input {
+            Some(text) => frame.push(format!("New timer (name=time): {text}_")),
+            None => frame.push("Timers"),
+        }
+
+        let width = self
+            .timers
+            .iter()
+            .map(|timer| timer.name.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (i, timer) in self.timers.iter().enumerate() {
+            let marker = if i == self.selected { '>' } else { ' ' };
+            let elapsed = timer.clock.elapsed();
+            let line = if timer.has_ended() {
+                let excess_time = format_duration(elapsed.saturating_sub(timer.target()));
+                format!("{marker} {:width$}  +{excess_time} ended", timer.name).with(Color::Red)
+            } else {
+                let time_left = format_duration(timer.target().saturating_sub(elapsed));
+                format!("{marker} {:width$}  {time_left}", timer.name)
+                    .with(timer.warnings.color(timer.clock.is_running()))
+            };
+            frame.push(line);
+        }
+        if self.timers.is_empty() {
+            frame.push("No timers, press [A] to add one");
+        }
+
+        match self.input {
+            Some(_) => frame.push(INPUT_PROMPT),
+            None => frame.push(&self.controls),
+        }
+        if let Some(error) = &self.error {
+            frame.push(error.as_str().with(Color::Red));
+        }
+    }
+
+    fn handle_action(&mut self, key: KeyEvent) -> Result<Flow> {
+        if self.input.is_some() {
+            self.handle_input(key);
+            return Ok(Flow::Continue);
+        }
+
+        match key.code {
+            KeyCode::Char('q') => return Ok(Flow::Quit),
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.selected = (self.selected + 1).min(self.timers.len().saturating_sub(1));
+            }
+            KeyCode::Char(' ') => {
+                if let Some(timer) = self.selected() {
+                    timer.clock.toggle();
+                }
+            }
+            KeyCode::Char('r') => {
+                if let Some(timer) = self.selected() {
+                    timer.clock.reset();
+                    timer.extension = Extension::default();
+                    timer.alerted = false;
+                }
+            }
+            KeyCode::Char('+') => self.extend(self.extend.short),
+            KeyCode::Char('>') => self.extend(self.extend.long),
+            KeyCode::Char('a') => self.input = Some(String::new()),
+            KeyCode::Char('d') if self.selected < self.timers.len() => {
+                self.timers.remove(self.selected);
+                self.selected = self.selected.min(self.timers.len().saturating_sub(1));
+            }
+            _ => {}
+        }
+        Ok(Flow::Continue)
+    }
+}
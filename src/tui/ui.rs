@@ -0,0 +1,192 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+use crossterm::style::{Color, StyledContent};
+
+use crate::history::{Interruption, InterruptionKind};
+use crate::prelude::*;
+use crate::tui::layout::{button_at, lay_out};
+
+/// A single line of text drawn in one color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub text: String,
+    pub color: Option<Color>,
+}
+
+impl From<&str> for Line {
+    fn from(text: &str) -> Self {
+        Self::from(text.to_string())
+    }
+}
+
+impl From<&String> for Line {
+    fn from(text: &String) -> Self {
+        Self::from(text.clone())
+    }
+}
+
+impl From<String> for Line {
+    fn from(text: String) -> Self {
+        Self { text, color: None }
+    }
+}
+
+impl<D: Display> From<StyledContent<D>> for Line {
+    fn from(content: StyledContent<D>) -> Self {
+        Self {
+            text: content.content().to_string(),
+            color: content.style().foreground_color,
+        }
+    }
+}
+
+/// Everything a counter shows on one redraw.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frame {
+    pub lines: Vec<Line>,
+    /// For the terminal's window title.
+    pub title: Option<String>,
+    /// How far along the counter is, in percent.
+    pub progress: Option<u8>,
+}
+
+impl Frame {
+    pub fn push(&mut self, line: impl Into<Line>) {
+        self.lines.push(line.into());
+    }
+
+    pub fn set_title(&mut self, title: impl Into<String>) {
+        self.title = Some(title.into());
+    }
+
+    pub fn set_progress(&mut self, elapsed: Duration, target: Duration) {
+        let percent = match target.is_zero() {
+            true => 100,
+            false => (elapsed.as_secs_f64() / target.as_secs_f64() * 100.0).min(100.0) as u8,
+        };
+        self.progress = Some(percent);
+    }
+
+    /// The frame as plain text, one line per row.
+    pub fn text(&self) -> String {
+        let lines: Vec<&str> = self.lines.iter().map(|line| line.text.as_str()).collect();
+        lines.join("\n")
+    }
+}
+
+/// Where frames end up: the terminal, or memory when testing.
+pub trait Screen {
+    fn draw(&mut self, frame: &Frame) -> Result<()>;
+
+    /// The terminal is now `width` columns by `height` rows.
+    fn resize(&mut self, _width: u16, _height: u16) {}
+
+    /// The key for the button drawn at `column` and `row`, if any.
+    fn button_at(&self, _column: u16, _row: u16) -> Option<KeyCode> {
+        None
+    }
+}
+
+/// What [`MemoryScreen`] lays frames out in until it's resized.
+pub const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// Keeps every distinct frame that was drawn, in order.
+#[derive(Debug, Default)]
+pub struct MemoryScreen {
+    frames: Vec<Frame>,
+    size: Option<(u16, u16)>,
+}
+
+impl MemoryScreen {
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// The size from the last resize, if there was one.
+    pub fn size(&self) -> Option<(u16, u16)> {
+        self.size
+    }
+
+    pub fn last(&self) -> Option<&Frame> {
+        self.frames.last()
+    }
+
+    /// Whether any frame so far contained `text`.
+    pub fn showed(&self, text: &str) -> bool {
+        self.frames.iter().any(|frame| frame.text().contains(text))
+    }
+}
+
+impl Screen for MemoryScreen {
+    fn draw(&mut self, frame: &Frame) -> Result<()> {
+        if self.frames.last() != Some(frame) {
+            self.frames.push(frame.clone());
+        }
+        Ok(())
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.size = Some((width, height));
+    }
+
+    fn button_at(&self, column: u16, row: u16) -> Option<KeyCode> {
+        let (width, height) = self.size.unwrap_or(DEFAULT_SIZE);
+        let placed = lay_out(&self.frames.last()?.lines, width, height);
+        button_at(&placed, column, row)
+    }
+}
+
+pub fn show_ui(
+    frame: &mut Frame,
+    title: impl Into<Line>,
+    clock: impl Into<Line>,
+    controls: impl Into<Line>,
+) {
+    frame.push(title);
+    frame.push(clock);
+    frame.push(controls);
+}
+
+/// The pomodoro details listed below the controls.
+pub struct PomoInfo<'a> {
+    pub session: u32,
+    pub task: Option<&'a str>,
+    pub interruptions: &'a [Interruption],
+    /// How long the pomodoro has been paused, while it is.
+    pub paused: Option<Line>,
+    /// Progress towards the daily goal, if one is set.
+    pub today: Option<String>,
+}
+
+pub fn show_pomo_ui(
+    frame: &mut Frame,
+    title: impl Into<Line>,
+    clock: impl Into<Line>,
+    controls: impl Into<Line>,
+    info: &PomoInfo,
+) {
+    frame.push(title);
+    frame.push(clock);
+    frame.push(controls);
+    frame.push(format!("Session: {}", info.session));
+    if let Some(paused) = &info.paused {
+        frame.push(paused.clone());
+    }
+    if let Some(today) = &info.today {
+        frame.push(today);
+    }
+    if let Some(task) = info.task {
+        frame.push(format!("Task: {}", task));
+    }
+    let interruptions = info.interruptions;
+    if !interruptions.is_empty() {
+        let count = |kind| interruptions.iter().filter(|i| i.kind == kind).count();
+        frame.push(format!(
+            "Interruptions: {} internal, {} external",
+            count(InterruptionKind::Internal),
+            count(InterruptionKind::External),
+        ));
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use crossterm::style::Color;
+
+use crate::config::Config;
+use crate::format::format_duration_short;
+use crate::hooks;
+use crate::interval::Cue;
+use crate::prelude::*;
+use crate::routine::Sound;
+use crate::tui::alert::Alerts;
+use crate::tui::terminal::running_color;
+use crate::warning::{Warning, Warnings};
+
+/// Plays the configured warnings and runs the warning hook.
+#[derive(Clone, Default)]
+pub struct WarningCues {
+    warnings: Warnings,
+    sound: Sound,
+    color: Option<Color>,
+    hook: Option<String>,
+    /// Why the hook couldn't be started.
+    error: Option<String>,
+}
+
+impl WarningCues {
+    pub fn new(config: &Config) -> Result<Self> {
+        let color = match &config.warnings.color {
+            Some(color) => Some(
+                Color::try_from(color.as_str())
+                    .map_err(|_| anyhow!("Unknown warning color {color:?}"))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            warnings: Warnings::new(&config.warnings),
+            sound: config.warnings.sound,
+            color,
+            hook: config.hooks.warning.clone(),
+            error: None,
+        })
+    }
+
+    /// See [`Warnings::check`], `label` names the phase for the hook. A hook
+    /// that fails to start doesn't stop anything, see
+    /// [`WarningCues::take_error`].
+    pub fn tick(
+        &mut self,
+        alerts: &mut Alerts,
+        phase: usize,
+        label: &str,
+        remaining: Duration,
+        target: Duration,
+    ) {
+        match self.warnings.check(phase, remaining, target) {
+            Some(Warning::Before(at)) => {
+                alerts.sound(self.sound);
+                if let Some(hook) = &self.hook {
+                    let env = [
+                        ("PORSMO_LABEL", label.to_string()),
+                        ("PORSMO_REMAINING", format_duration_short(at)),
+                    ];
+                    if let Err(e) = hooks::run(hook, "warning", &env) {
+                        self.error = Some(format!("{e:#}"));
+                    }
+                }
+            }
+            Some(Warning::Countdown(_)) => alerts.cue(Cue::Countdown),
+            None => {}
+        }
+    }
+
+    /// Why the last warning hook failed, once.
+    pub fn take_error(&mut self) -> Option<String> {
+        self.error.take()
+    }
+
+    /// The clock color, switched to the warning color once one fired.
+    pub fn color(&self, is_running: bool) -> Color {
+        match self.color {
+            Some(color) if is_running && self.warnings.fired() => color,
+            _ => running_color(is_running),
+        }
+    }
+}
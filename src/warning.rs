@@ -0,0 +1,119 @@
+//! Heads-ups before a phase or timer runs out.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::config::{duration, durations};
+use crate::routine::Sound;
+
+/// Set under `[warnings]`, there are none by default.
+///
+/// ```toml
+/// [warnings]
+/// at = ["5m", "1m"]
+/// countdown = "10s"
+/// sound = "high"
+/// color = "yellow"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WarningConfig {
+    /// Time left at which to warn.
+    #[serde(with = "durations")]
+    pub at: Vec<Duration>,
+    /// Tick every second once this little time is left.
+    #[serde(with = "duration")]
+    pub countdown: Duration,
+    pub sound: Sound,
+    /// Color of the clock once a warning fired.
+    pub color: Option<String>,
+}
+
+impl Default for WarningConfig {
+    fn default() -> Self {
+        Self {
+            at: Vec::new(),
+            countdown: Duration::ZERO,
+            sound: Sound::High,
+            color: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warning {
+    /// One of the configured thresholds was crossed.
+    Before(Duration),
+    /// A second of the final countdown, counted down to 1.
+    Countdown(u64),
+}
+
+/// Keeps track of which warnings fired for the current phase.
+#[derive(Debug, Clone, Default)]
+pub struct Warnings {
+    at: Vec<Duration>,
+    countdown: u64,
+    phase: Option<usize>,
+    fired: Vec<Duration>,
+    counted: Option<u64>,
+    /// What fired during the phases before, for going back to one.
+    earlier: HashMap<usize, (Vec<Duration>, Option<u64>)>,
+}
+
+impl Warnings {
+    pub fn new(config: &WarningConfig) -> Self {
+        Self {
+            at: config.at.clone(),
+            countdown: config.countdown.as_secs(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether any warning fired during the current phase.
+    pub fn fired(&self) -> bool {
+        !self.fired.is_empty() || self.counted.is_some()
+    }
+
+    /// Returns the warning that became due, if any. `phase` identifies the
+    /// current phase, warnings only start over for a phase that wasn't seen
+    /// before, so pausing, resetting or going back to a phase never fires
+    /// one twice. Thresholds that aren't shorter than `target` never fire.
+    pub fn check(
+        &mut self,
+        phase: usize,
+        remaining: Duration,
+        target: Duration,
+    ) -> Option<Warning> {
+        if self.phase != Some(phase) {
+            let (fired, counted) = self.earlier.remove(&phase).unwrap_or_default();
+            let fired = std::mem::replace(&mut self.fired, fired);
+            let counted = std::mem::replace(&mut self.counted, counted);
+            if let Some(left) = self.phase.replace(phase) {
+                self.earlier.insert(left, (fired, counted));
+            }
+        }
+        if remaining.is_zero() {
+            return None;
+        }
+
+        let crossed: Vec<Duration> = self
+            .at
+            .iter()
+            .copied()
+            .filter(|at| *at < target && remaining <= *at && !self.fired.contains(at))
+            .collect();
+        if let Some(at) = crossed.iter().min().copied() {
+            self.fired.extend(crossed);
+            return Some(Warning::Before(at));
+        }
+
+        let second = remaining.as_millis().div_ceil(1000) as u64;
+        if second <= self.countdown && self.counted.is_none_or(|counted| second < counted) {
+            self.counted = Some(second);
+            return Some(Warning::Countdown(second));
+        }
+        None
+    }
+}
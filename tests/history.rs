@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use chrono::{Local, TimeZone, Utc};
+use porsmo::export::{between, parse_date, to_csv, to_ics};
+use porsmo::goal::{streaks, GoalConfig, Streaks};
+use porsmo::history::{self, PhaseRecord};
+use porsmo::import::merge;
+use porsmo::pomodoro::Mode;
+use uuid::Uuid;
+
+fn record(mode: Mode, task: Option<&str>) -> PhaseRecord {
+    let start = Local.with_ymd_and_hms(2024, 3, 5, 9, 0, 0).unwrap();
+    PhaseRecord {
+        id: Uuid::nil(),
+        start,
+        end: start + chrono::Duration::minutes(25),
+        mode,
+        session: 1,
+        planned: Duration::from_secs(25 * 60),
+        extended: Duration::ZERO,
+        elapsed: Duration::from_secs(25 * 60),
+        skipped: false,
+        pauses: 1,
+        paused: Duration::from_secs(90),
+        interruptions: Vec::new(),
+        task: task.map(str::to_string),
+        host: None,
+    }
+}
+
+#[test]
+fn csv_quotes_tasks() {
+    let csv = to_csv(&[record(Mode::Work, Some("review, \"fix\" bugs"))]);
+    let rows: Vec<&str> = csv.lines().collect();
+    assert_eq!(rows.len(), 2);
+    assert!(rows[0].starts_with("id,date,start,end,mode,session,task,"));
+    assert!(
+        rows[1].contains(",work,1,\"review, \"\"fix\"\" bugs\",1500,1500,25m 0s,0,false,1,90,0,")
+    );
+}
+
+#[test]
+fn csv_times_cross_midnight() {
+    let mut late = record(Mode::Work, None);
+    late.start = Local.with_ymd_and_hms(2024, 3, 5, 23, 50, 0).unwrap();
+    late.end = late.start + chrono::Duration::minutes(25);
+    let csv = to_csv(&[late.clone()]);
+    let row: Vec<&str> = csv.lines().nth(1).unwrap().split(',').collect();
+    assert_eq!(row[1], "2024-03-05");
+    assert_eq!(row[2], late.start.to_rfc3339());
+    assert_eq!(row[3], late.end.to_rfc3339());
+    assert!(row[3].starts_with("2024-03-06T00:15:00"));
+}
+
+#[test]
+fn ics_has_an_event_per_work_phase() {
+    let records = [
+        record(Mode::Work, Some("write; docs")),
+        record(Mode::Break, None),
+    ];
+    let ics = to_ics(&records, Utc.with_ymd_and_hms(2024, 3, 6, 0, 0, 0).unwrap());
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 1);
+    assert!(ics.contains("\r\nSUMMARY:write\\; docs\r\n"));
+    assert!(ics.contains("\r\nDESCRIPTION:Session 1\\, 25m 0s of 25m 0s\\, 1 pause\r\n"));
+    assert!(ics.lines().all(|line| line.len() <= 75));
+}
+
+#[test]
+fn dates_are_inclusive() {
+    let records = vec![record(Mode::Work, None)];
+    let day = parse_date("2024-03-05").unwrap();
+    assert_eq!(between(records.clone(), Some(day), Some(day)).len(), 1);
+    let next = parse_date("2024-03-06").unwrap();
+    assert!(between(records, Some(next), None).is_empty());
+    assert!(parse_date("05/03/2024").is_err());
+}
+
+fn record_at(hour: u32, min: u32, host: Option<&str>) -> PhaseRecord {
+    let mut record = record(Mode::Work, None);
+    record.id = Uuid::new_v4();
+    record.start = Local.with_ymd_and_hms(2024, 3, 5, hour, min, 0).unwrap();
+    record.end = record.start + chrono::Duration::minutes(25);
+    record.host = host.map(str::to_string);
+    record
+}
+
+#[test]
+fn import_skips_duplicates_and_overlaps() {
+    let existing = vec![record_at(9, 0, Some("desktop"))];
+    let incoming = vec![
+        existing[0].clone(),
+        record_at(9, 10, None),
+        record_at(10, 0, None),
+        record_at(10, 20, Some("tablet")),
+    ];
+
+    // Only entries without a host need one.
+    assert!(merge(&existing, incoming.clone(), None, false).is_err());
+    assert!(merge(&existing, incoming[3..].to_vec(), None, false).is_ok());
+    let merged = merge(&existing, incoming.clone(), Some("laptop"), false).unwrap();
+    assert_eq!(merged.duplicates, 1);
+    assert_eq!(merged.overlapping, 2);
+    assert_eq!(merged.added.len(), 1);
+    assert_eq!(merged.added[0].id, incoming[2].id);
+    assert_eq!(merged.added[0].host.as_deref(), Some("laptop"));
+
+    let merged = merge(&existing, incoming, Some("laptop"), true).unwrap();
+    assert_eq!(merged.added.len(), 3);
+    assert_eq!(merged.added[2].host.as_deref(), Some("tablet"));
+}
+
+#[test]
+fn goal_streaks_count_consecutive_days() {
+    let goal = GoalConfig {
+        pomodoros: Some(2),
+        focus: None,
+    };
+    let on = |day: u32, pomodoros: usize| {
+        (0..pomodoros).map(move |_| {
+            let mut record = record(Mode::Work, None);
+            record.start = Local.with_ymd_and_hms(2024, 3, day, 9, 0, 0).unwrap();
+            record
+        })
+    };
+    // Met on the 1st to 3rd, missed on the 4th, met again on the 5th and 6th.
+    let records: Vec<PhaseRecord> = [(1, 2), (2, 3), (3, 2), (4, 1), (5, 2), (6, 2), (7, 1)]
+        .into_iter()
+        .flat_map(|(day, pomodoros)| on(day, pomodoros))
+        .collect();
+
+    let day = |day| parse_date(&format!("2024-03-{day:02}")).unwrap();
+    // Today isn't over yet, so a missed goal doesn't break the streak.
+    assert_eq!(
+        streaks(&records, &goal, day(7)),
+        Streaks {
+            current: 2,
+            best: 3
+        }
+    );
+    assert_eq!(
+        streaks(&records, &goal, day(6)),
+        Streaks {
+            current: 2,
+            best: 3
+        }
+    );
+    assert_eq!(
+        streaks(&records, &goal, day(9)),
+        Streaks {
+            current: 0,
+            best: 3
+        }
+    );
+}
+
+#[test]
+fn removed_records_leave_the_history() {
+    let path = std::env::temp_dir().join(format!("porsmo-remove-{}.jsonl", std::process::id()));
+    let kept = record(Mode::Work, Some("kept"));
+    let mut removed = record(Mode::Work, Some("removed"));
+    removed.id = Uuid::new_v4();
+    history::append_to(&path, &kept).unwrap();
+    history::append_to(&path, &removed).unwrap();
+
+    history::remove_from(&path, removed.id).unwrap();
+
+    let records = history::load_from(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].task.as_deref(), Some("kept"));
+}
+
+#[test]
+fn readable_records_survive_a_cut_off_line() {
+    let path = std::env::temp_dir().join(format!("porsmo-cut-{}.jsonl", std::process::id()));
+    history::append_to(&path, &record(Mode::Work, None)).unwrap();
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    std::io::Write::write_all(&mut file, b"{\"id\":\"0000").unwrap();
+
+    let strict = history::load_from(&path);
+    let (records, skipped) = history::load_readable(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(strict.is_err());
+    assert_eq!(records.len(), 1);
+    assert_eq!(skipped, 1);
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use chrono::Local;
+use porsmo::clock::TimeSource;
+use porsmo::pomodoro::{Durations, Mode, Pomodoro};
+
+fn phases(pomodoro: &mut Pomodoro, count: usize) -> Vec<(Mode, u32)> {
+    (0..count)
+        .map(|_| {
+            pomodoro.advance().unwrap();
+            (pomodoro.mode(), pomodoro.session())
+        })
+        .collect()
+}
+
+#[test]
+fn long_breaks_come_every_fourth_session() {
+    let mut pomodoro = Pomodoro::new(Durations::SHORT);
+    let modes: Vec<Mode> = phases(&mut pomodoro, 8)
+        .into_iter()
+        .map(|(mode, _)| mode)
+        .collect();
+    assert_eq!(modes[4], Mode::Break);
+    assert_eq!(modes[5], Mode::Work);
+    assert_eq!(modes[6], Mode::LongBreak);
+    assert_eq!(modes[7], Mode::Work);
+}
+
+#[test]
+fn an_early_long_break_restarts_the_cycle() {
+    let mut pomodoro = Pomodoro::new(Durations::SHORT);
+    pomodoro.goto(Mode::LongBreak).unwrap();
+    assert_eq!(pomodoro.session(), 1);
+    assert!(pomodoro.records()[0].skipped);
+
+    let phases = phases(&mut pomodoro, 8);
+    assert_eq!(phases[0], (Mode::Work, 2));
+    assert_eq!(phases[5], (Mode::Break, 4));
+    assert_eq!(phases[7], (Mode::LongBreak, 5));
+}
+
+#[test]
+fn going_back_restores_the_phase() {
+    let mut pomodoro = Pomodoro::new(Durations::SHORT);
+    pomodoro.toggle().unwrap();
+    pomodoro.extend(Durations::SHORT.work).unwrap();
+    pomodoro.skip().unwrap();
+    assert_eq!(pomodoro.mode(), Mode::Break);
+    assert_eq!(pomodoro.phase(), 2);
+
+    assert!(pomodoro.back().unwrap());
+    assert_eq!(pomodoro.mode(), Mode::Work);
+    assert_eq!(pomodoro.session(), 1);
+    assert_eq!(pomodoro.phase(), 1);
+    assert!(!pomodoro.is_running());
+    assert_eq!(pomodoro.target(), Durations::SHORT.work * 2);
+    assert!(pomodoro.records().is_empty());
+    assert!(!pomodoro.back().unwrap());
+
+    // The break that was gone back from doesn't come back.
+    pomodoro.skip().unwrap();
+    assert_eq!(pomodoro.phase(), 3);
+}
+
+#[test]
+fn overtime_stays_overtime_when_extending() {
+    let time = TimeSource::manual(Local::now());
+    let mut pomodoro = Pomodoro::new(Durations::SHORT).with_time(time.clone());
+    let work = Durations::SHORT.work;
+    let extra = Duration::from_secs(60);
+    time.advance(work + extra);
+    pomodoro.extend(extra).unwrap();
+    assert_eq!(pomodoro.target(), work + extra * 2);
+    assert_eq!(pomodoro.remaining(), extra);
+
+    // Going back brings the time that was up back with the extension.
+    time.advance(extra * 2);
+    pomodoro.skip().unwrap();
+    assert!(pomodoro.back().unwrap());
+    assert_eq!(pomodoro.target(), work + extra * 2);
+
+    let record = &pomodoro.finish().unwrap()[0];
+    assert_eq!(record.extended, extra);
+    assert_eq!(record.overtime(), extra * 2);
+}
@@ -0,0 +1,908 @@
+#![cfg(feature = "tui")]
+
+use std::time::Duration;
+
+use crossterm::event::KeyCode;
+use crossterm::style::Color;
+use porsmo::config::{Config, ExtendConfig};
+use porsmo::pomodoro::{Durations, Mode};
+use porsmo::tui::alert::Alerts;
+use porsmo::tui::app::{run_with, Counter};
+use porsmo::tui::input::Script;
+use porsmo::tui::ui::{Frame, MemoryScreen};
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+fn mins(mins: u64) -> Duration {
+    Duration::from_secs(mins * 60)
+}
+
+fn config(toml: &str) -> Config {
+    toml::from_str(toml).unwrap()
+}
+
+/// Replays `script` on `counter`, with alerts that are only counted.
+fn run(counter: &mut impl Counter, mut script: Script) -> (MemoryScreen, Alerts) {
+    let mut screen = MemoryScreen::default();
+    let mut alerts = Alerts::silent();
+    run_with(counter, &mut screen, &mut script, &mut alerts).unwrap();
+    (screen, alerts)
+}
+
+mod pomodoro {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use chrono::{DateTime, Local, TimeZone};
+    use porsmo::goal::Progress;
+    use porsmo::history::{self, PhaseRecord};
+    use porsmo::interval::Cue;
+    use porsmo::tui::pomodoro::PomodoroApp;
+    use porsmo::tui::warning::WarningCues;
+
+    use super::*;
+
+    /// A short pomodoro without a task or a history.
+    fn pomodoro(toml: &str) -> PomodoroApp {
+        PomodoroApp::new(Durations::SHORT, None, &config(toml))
+    }
+
+    /// Today at `hour:min`.
+    fn at(hour: u32, min: u32) -> DateTime<Local> {
+        let date = Local::now().date_naive();
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, min, 0).unwrap())
+            .unwrap()
+    }
+
+    /// A history file in a directory of its own for `test`.
+    fn history_path(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("porsmo-{}-{test}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("history.jsonl")
+    }
+
+    #[test]
+    fn work_then_break() {
+        let script = Script::default()
+            .press(secs(1), KeyCode::Char('t'))
+            .type_text(secs(1), "review PR 42")
+            .press(secs(1), KeyCode::Enter)
+            .press(mins(10), KeyCode::Char('i'))
+            .press(mins(10), KeyCode::Enter)
+            .press(mins(25) + secs(2), KeyCode::Enter)
+            .press(mins(27), KeyCode::Char('q'));
+        let mut app = pomodoro("").with_time(script.time());
+
+        let (screen, alerts) = run(&mut app, script);
+
+        assert!(screen.showed("Task: review PR 42_"));
+        assert!(screen.showed("Interruptions: 1 internal, 0 external"));
+        assert!(screen.showed("Enjoy your break!"));
+        assert_eq!(alerts.played(), 1);
+        let first = &screen.frames()[0];
+        assert_eq!(first.title.as_deref(), Some("🍅 25:00 Work #1"));
+        assert_eq!(first.progress, Some(0));
+        let titles: Vec<_> = screen
+            .frames()
+            .iter()
+            .filter_map(|f| f.title.as_deref())
+            .collect();
+        assert!(titles.contains(&"🍅 +00:01 Work #1"));
+        assert!(titles.contains(&"☕ 04:59 Break #1"));
+
+        let time_up = [
+            "Time for a break!",
+            "+0h 0m 1s",
+            "[Q]: Quit, [Enter]: Next, [Space]: Pause/Resume, [R]: Reset, \
+            [+/>]: +1m 0s/+5m 0s, [T]: Task, [?]: Help",
+            "Session: 1",
+            "Task: review PR 42",
+            "Interruptions: 1 internal, 0 external",
+        ]
+        .join("\n");
+        assert!(screen.frames().iter().any(|frame| frame.text() == time_up));
+
+        let records = app.finish().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].mode, Mode::Work);
+        assert_eq!(records[0].elapsed.as_secs(), 25 * 60 + 2);
+        let took = records[0].end - records[0].start;
+        assert_eq!(took.num_seconds(), 25 * 60 + 2);
+        assert_eq!(records[0].task.as_deref(), Some("review PR 42"));
+        assert_eq!(records[0].interruptions.len(), 1);
+        assert_eq!(records[1].mode, Mode::Break);
+        assert_eq!(records[1].elapsed.as_secs(), 2 * 60 - 2);
+    }
+
+    #[test]
+    fn skip_needs_confirmation() {
+        let script = Script::default()
+            .press(mins(5), KeyCode::Char('s'))
+            .press(mins(5), KeyCode::Char('n'))
+            .press(mins(6), KeyCode::Char('s'))
+            .press(mins(6), KeyCode::Char('y'))
+            .press(mins(7), KeyCode::Char('q'));
+        let mut app = pomodoro("").with_time(script.time());
+
+        let (screen, _) = run(&mut app, script);
+
+        assert!(screen.showed("Skip this session?"));
+        let records = app.finish().unwrap();
+        assert!(records[0].skipped);
+        assert_eq!(records[0].elapsed.as_secs(), 6 * 60);
+        assert_eq!(records[1].mode, Mode::Break);
+    }
+
+    #[test]
+    fn can_confirm_resets() {
+        let script = Script::default()
+            .press(mins(5), KeyCode::Char('r'))
+            .press(mins(5), KeyCode::Char('n'))
+            .press(mins(6), KeyCode::Char('q'));
+        let mut app = pomodoro("[confirm]\nreset = true").with_time(script.time());
+
+        let (screen, _) = run(&mut app, script);
+
+        assert!(screen.showed("Reset this session?"));
+        let records = app.finish().unwrap();
+        assert_eq!(records[0].elapsed.as_secs(), 6 * 60);
+    }
+
+    #[test]
+    fn undoes_resets_and_skips() {
+        let script = Script::default()
+            .press(mins(10), KeyCode::Char('r'))
+            .press(mins(11), KeyCode::Char('u'))
+            .press(mins(12), KeyCode::Char('s'))
+            .press(mins(12), KeyCode::Char('y'))
+            .press(mins(13), KeyCode::Char('u'))
+            .press(mins(13) + secs(30), KeyCode::Char('u'))
+            .press(mins(14), KeyCode::Char('q'));
+        let mut app = pomodoro("").with_time(script.time());
+
+        let (screen, _) = run(&mut app, script);
+
+        assert!(screen.showed("Reset — press u to undo"));
+        // The minute since the reset still counts.
+        assert!(screen.showed("Pomodoro (Work)\n0h 13m 59s"));
+        assert!(screen.showed("Skipped — press u to undo"));
+        assert!(screen.showed("Back to the previous phase"));
+        assert!(screen.showed("Nothing to undo"));
+        // The toast is gone after a while.
+        assert!(!screen.last().unwrap().text().contains("Back to"));
+
+        let records = app.finish().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].elapsed.as_secs(), 13 * 60);
+        assert!(!records[0].skipped);
+    }
+
+    #[test]
+    fn jumps_and_goes_back() {
+        let script = Script::default()
+            .press(mins(5), KeyCode::Char('l'))
+            .press(mins(6), KeyCode::Char('p'))
+            .press(mins(7), KeyCode::Char('p'))
+            .press(mins(8), KeyCode::Char('q'));
+        let mut app = pomodoro("").with_time(script.time());
+
+        let (screen, _) = run(&mut app, script);
+
+        assert!(screen.showed("Give your mind some rest!\n0h 9m 59s"));
+        // Picked up where it was left, the minute on the long break is gone.
+        assert!(screen.showed("Pomodoro (Work)\n0h 19m 59s"));
+        assert!(screen.showed("This is the first phase"));
+
+        let records = app.finish().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].mode, Mode::Work);
+        assert_eq!(records[0].elapsed.as_secs(), 7 * 60);
+        assert!(!records[0].skipped);
+    }
+
+    #[test]
+    fn going_back_doesnt_warn_again() {
+        let toml = "[warnings]\nat = [\"5m\"]";
+        let script = Script::default()
+            .press(mins(21), KeyCode::Char('l'))
+            .press(mins(22), KeyCode::Char('p'))
+            .press(mins(23), KeyCode::Char('q'));
+        let mut app = pomodoro(toml)
+            .with_warnings(WarningCues::new(&config(toml)).unwrap())
+            .with_time(script.time());
+
+        let (screen, alerts) = run(&mut app, script);
+
+        assert!(screen.showed("Pomodoro (Work)\n0h 3m 59s"));
+        assert_eq!(alerts.cues(), [Cue::Start]);
+    }
+
+    #[test]
+    fn going_back_holds_off_auto_start() {
+        let script = Script::default()
+            .press(mins(26), KeyCode::Char('p'))
+            .press(mins(27), KeyCode::Char('+'))
+            .press(mins(30), KeyCode::Char('q'));
+        let mut app = pomodoro("[auto_start]\nbreaks = true").with_time(script.time());
+
+        let (screen, _) = run(&mut app, script);
+
+        assert!(screen.showed("Time for a break!\n+0h 0m 59s"));
+        let records = app.finish().unwrap();
+        assert_eq!(records.len(), 2);
+        // Picked up again and extended, then the break started by itself.
+        assert_eq!(records[0].mode, Mode::Work);
+        assert_eq!(records[0].elapsed.as_secs(), 27 * 60);
+        assert_eq!(records[1].mode, Mode::Break);
+    }
+
+    #[test]
+    fn writes_the_history() {
+        let path = history_path("writes_the_history");
+        let script = Script::default()
+            .press(mins(5), KeyCode::Char('l'))
+            .press(mins(6), KeyCode::Char('p'))
+            .press(mins(7), KeyCode::Char('l'))
+            .press(mins(8), KeyCode::Char('q'));
+        let mut app = pomodoro("")
+            .with_history(path.clone())
+            .with_time(script.time());
+
+        run(&mut app, script);
+        let written = history::load_from(&path).unwrap();
+        let records = app.finish().unwrap();
+        let saved = history::load_from(&path).unwrap();
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        // The work phase that was gone back to was taken out again.
+        assert_eq!(written.len(), 1);
+        assert_eq!(written[0].elapsed.as_secs(), 6 * 60);
+        assert_eq!(saved.len(), 2);
+        assert_eq!(saved[1].mode, Mode::LongBreak);
+        let ids = |records: &[PhaseRecord]| records.iter().map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(ids(&saved), ids(&records));
+    }
+
+    #[test]
+    fn keeps_running_when_the_history_cant_be_written() {
+        let dir = history_path("keeps_running_when_the_history_cant_be_written");
+        // A file where the history's directory should be.
+        let blocker = dir.with_file_name("blocker");
+        fs::write(&blocker, "").unwrap();
+        let path = blocker.join("history.jsonl");
+        let script = Script::default()
+            .press(mins(5), KeyCode::Char('l'))
+            .press(mins(6), KeyCode::Char('q'));
+        let mut app = pomodoro("")
+            .with_history(path.clone())
+            .with_time(script.time());
+
+        let (screen, _) = run(&mut app, script);
+        fs::remove_file(&blocker).unwrap();
+        let records = app.finish().unwrap();
+        let saved = history::load_from(&path).unwrap();
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+
+        assert!(screen.showed("History not saved: Failed to create"));
+        assert!(screen.showed("Long break"));
+        assert_eq!(saved.len(), 2);
+        let ids = |records: &[PhaseRecord]| records.iter().map(|r| r.id).collect::<Vec<_>>();
+        assert_eq!(ids(&saved), ids(&records));
+    }
+
+    #[test]
+    fn durations_change_while_running() {
+        // Work goes from 25m to 26m, then 24m, so Enter only works from 24m on.
+        let script = Script::default()
+            .press(mins(2), KeyCode::Char(']'))
+            .press(mins(3), KeyCode::Char('['))
+            .press(mins(3), KeyCode::Char('['))
+            .press(mins(24) + secs(1), KeyCode::Enter)
+            .press(mins(28) + secs(1), KeyCode::Char(':'))
+            .type_text(mins(28) + secs(1), "set 3m")
+            .press(mins(28) + secs(1), KeyCode::Enter)
+            .press(mins(28) + secs(2), KeyCode::Char(':'))
+            .type_text(mins(28) + secs(2), "set break 7m")
+            .press(mins(28) + secs(2), KeyCode::Enter)
+            .press(mins(29), KeyCode::Char('q'));
+        let mut app = pomodoro("").with_time(script.time());
+
+        let (screen, alerts) = run(&mut app, script);
+
+        // Shortened past the elapsed time, so it's over right away.
+        assert!(screen.showed("Time to start working!\n+0h 1m 0s"));
+        // Lengthened past it again.
+        assert!(screen
+            .last()
+            .unwrap()
+            .text()
+            .starts_with("Enjoy your break!"));
+        assert_eq!(alerts.played(), 2);
+
+        let records = app.finish().unwrap();
+        assert_eq!(records[0].planned, mins(24));
+        assert_eq!(records[0].elapsed.as_secs(), 24 * 60 + 1);
+        assert!(!records[0].skipped);
+        assert_eq!(records[1].planned, mins(7));
+    }
+
+    #[test]
+    fn help_and_commands() {
+        let script = Script::default()
+            .press(secs(1), KeyCode::Char('?'))
+            .press(secs(2), KeyCode::Esc)
+            .press(mins(1), KeyCode::Char(':'))
+            .type_text(mins(1), "set work 10m")
+            .press(mins(1), KeyCode::Enter)
+            .press(mins(2), KeyCode::Char(':'))
+            .type_text(mins(2), "sleep 5m")
+            .press(mins(2), KeyCode::Enter)
+            .press(mins(3), KeyCode::Char(':'))
+            .type_text(mins(3), "task write docs")
+            .press(mins(3), KeyCode::Enter)
+            .press(mins(4), KeyCode::Char(':'))
+            .type_text(mins(4), "goto long-break")
+            .press(mins(4), KeyCode::Enter)
+            .press(mins(5), KeyCode::Char(':'))
+            .type_text(mins(5), "extend 5m")
+            .press(mins(5), KeyCode::Enter)
+            .press(mins(6), KeyCode::Char('q'));
+        let mut app = pomodoro("").with_time(script.time());
+
+        let (screen, _) = run(&mut app, script);
+
+        let help = screen
+            .frames()
+            .iter()
+            .find(|frame| frame.lines[0].text == "Help")
+            .unwrap()
+            .text();
+        assert!(help.contains("S      Skip this phase, asks first"));
+        assert!(help.contains("I      Note an internal interruption"));
+        assert!(help.contains("set [phase] <time>  Change how long phases last"));
+        assert!(screen.showed(":set work 10m_"));
+        assert!(screen.showed("Pomodoro (Work)\n0h 8m 59s"));
+        assert!(screen.showed("Unknown command \"sleep\""));
+        assert!(screen.showed("Give your mind some rest!\n0h 14m 0s"));
+
+        let records = app.finish().unwrap();
+        assert_eq!(records[0].mode, Mode::Work);
+        assert_eq!(records[0].planned, mins(10));
+        assert_eq!(records[0].task.as_deref(), Some("write docs"));
+        assert!(records[0].skipped);
+        assert_eq!(records[1].mode, Mode::LongBreak);
+    }
+
+    #[test]
+    fn tracks_pauses() {
+        let script = Script::default()
+            .press(mins(1), KeyCode::Char(' '))
+            .press(mins(2), KeyCode::Char(' '))
+            .press(mins(3), KeyCode::Char(' '))
+            .press(mins(6), KeyCode::Char(' '))
+            .press(mins(7), KeyCode::Char('q'));
+        let mut app = pomodoro("[pause]\nmax = \"2m\"").with_time(script.time());
+
+        let (screen, alerts) = run(&mut app, script);
+
+        assert!(screen.showed("Session: 1\nPaused 59s"));
+        assert!(screen.showed("Paused 1m 0s, 2 pauses, 2m 0s in total"));
+        let over = |frame: &Frame| {
+            frame
+                .lines
+                .iter()
+                .any(|line| line.text.starts_with("Paused 2m") && line.color == Some(Color::Red))
+        };
+        assert!(screen.frames().iter().any(over));
+        // The reminder, once.
+        assert_eq!(alerts.played(), 1);
+        assert!(!screen.last().unwrap().text().contains("Paused"));
+
+        let records = app.finish().unwrap();
+        assert_eq!(records[0].pauses, 2);
+        assert_eq!(records[0].paused.as_secs(), 4 * 60);
+        assert_eq!(records[0].elapsed.as_secs(), 3 * 60);
+    }
+
+    #[test]
+    fn resetting_ends_the_pause() {
+        let script = Script::default()
+            .press(mins(1), KeyCode::Char(' '))
+            .press(mins(2), KeyCode::Char('r'))
+            .press(mins(10), KeyCode::Char('q'));
+        let mut app = pomodoro("[pause]\nmax = \"2m\"").with_time(script.time());
+
+        let (screen, alerts) = run(&mut app, script);
+
+        assert!(screen.showed("Paused 59s"));
+        assert!(!screen.last().unwrap().text().contains("Paused"));
+        // No reminder for a phase that runs again.
+        assert_eq!(alerts.played(), 0);
+
+        let records = app.finish().unwrap();
+        assert_eq!(records[0].pauses, 1);
+        assert_eq!(records[0].paused.as_secs(), 60);
+        assert_eq!(records[0].elapsed.as_secs(), 8 * 60);
+    }
+
+    #[test]
+    fn pauses_when_idle() {
+        let script = Script::default()
+            .focus(secs(30), false)
+            .focus(mins(1), true)
+            // Only asks once back, the first key doesn't count.
+            .press(mins(10), KeyCode::Char('s'))
+            .press(mins(10), KeyCode::Char('d'))
+            .press(mins(11), KeyCode::Char('q'));
+        let mut app = pomodoro("[idle]\nafter = \"5m\"").with_time(script.time());
+
+        let (screen, _) = run(&mut app, script);
+
+        assert!(screen.showed("Pomodoro (Work)\n0h 15m 0s"));
+        assert!(screen.showed("Away for 9m 0s, keep that time?\n0h 15m 0s"));
+        assert!(!screen.showed("Skip this session?"));
+        let records = app.finish().unwrap();
+        assert_eq!(records[0].elapsed.as_secs(), 2 * 60);
+        assert_eq!(records[0].pauses, 1);
+    }
+
+    #[test]
+    fn idle_time_counts_from_the_start_of_the_phase() {
+        let script = Script::default()
+            .press(mins(33), KeyCode::Char('s'))
+            .press(mins(34), KeyCode::Char('q'));
+        let config = "[idle]\nafter = \"5m\"\n[auto_start]\nbreaks = true\nwork = true";
+        let mut app = pomodoro(config).with_time(script.time());
+
+        let (screen, _) = run(&mut app, script);
+
+        assert!(!screen.showed("keep that time?"));
+        assert!(screen.showed("Skip this session?"));
+    }
+
+    #[test]
+    fn keeps_working_when_idle_detection_fails() {
+        let script = Script::default()
+            .press(mins(1), KeyCode::Char('?'))
+            .press(mins(1), KeyCode::Esc)
+            .press(mins(10), KeyCode::Char('q'));
+        let config = "[idle]\nafter = \"5m\"\nsource = \"command\"\ncommand = \"exit 1\"";
+        let mut app = pomodoro(config).with_time(script.time());
+
+        let (screen, _) = run(&mut app, script);
+
+        assert!(screen.frames()[0].text().contains("Idle detection failed"));
+        // Only once, not after every poll.
+        assert!(!screen
+            .last()
+            .unwrap()
+            .text()
+            .contains("Idle detection failed"));
+        let records = app.finish().unwrap();
+        assert_eq!(records[0].elapsed.as_secs(), 10 * 60);
+        assert_eq!(records[0].pauses, 0);
+    }
+
+    #[test]
+    fn an_idle_command_is_required() {
+        let config = config("[idle]\nafter = \"5m\"\nsource = \"command\"");
+        assert!(config.idle.validate().is_err());
+    }
+
+    #[test]
+    fn celebrates_the_daily_goal() {
+        let done = Progress {
+            pomodoros: 1,
+            focus: mins(25),
+        };
+        let script = Script::starting_at(at(9, 0))
+            .press(mins(25) + secs(2), KeyCode::Enter)
+            .press(mins(27), KeyCode::Char('q'));
+        let mut app = pomodoro("[goal]\npomodoros = 2")
+            .with_done_today(done)
+            .with_time(script.time());
+
+        let (screen, alerts) = run(&mut app, script);
+
+        assert!(screen.frames()[0]
+            .text()
+            .contains("Session: 1\nToday: 1/2 🍅"));
+        assert!(screen.showed("Today: 2/2 🍅, goal reached!"));
+        // The end of work and the goal.
+        assert_eq!(alerts.played(), 2);
+    }
+
+    #[test]
+    fn the_daily_goal_starts_over_at_midnight() {
+        let done = Progress {
+            pomodoros: 1,
+            focus: mins(25),
+        };
+        let script = Script::starting_at(at(23, 50))
+            .press(mins(25) + secs(2), KeyCode::Enter)
+            .press(mins(27), KeyCode::Char('q'));
+        let mut app = pomodoro("[goal]\npomodoros = 2")
+            .with_done_today(done)
+            .with_time(script.time());
+
+        let (screen, alerts) = run(&mut app, script);
+
+        assert!(screen.frames()[0]
+            .text()
+            .contains("Session: 1\nToday: 1/2 🍅"));
+        // The pomodoro started before midnight counts for the day before.
+        assert!(screen.last().unwrap().text().contains("Today: 0/2 🍅"));
+        assert!(!screen.showed("goal reached!"));
+        assert_eq!(alerts.played(), 1);
+    }
+}
+
+mod timer {
+    use porsmo::interval::Cue;
+    use porsmo::routine::Repeat;
+    use porsmo::tui::timer::Timer;
+    use porsmo::tui::warning::WarningCues;
+
+    use super::*;
+
+    #[test]
+    fn alerts_again_after_extending() {
+        let script = Script::default()
+            .press(secs(90), KeyCode::Char('+'))
+            .press(mins(4), KeyCode::Char('q'));
+        let mut timer = Timer::new(mins(1), ExtendConfig::default()).with_time(script.time());
+
+        let (screen, alerts) = run(&mut timer, script);
+
+        assert!(screen.showed("Timer has ended\n+0h 0m 29s"));
+        assert_eq!(alerts.played(), 2);
+    }
+
+    #[test]
+    fn warns_once_before_ending() {
+        let config = config(
+            r#"
+            [warnings]
+            at = ["5m", "1m"]
+            countdown = "3s"
+            color = "yellow"
+            "#,
+        );
+        let script = Script::default()
+            .press(secs(70), KeyCode::Char(' '))
+            .press(secs(80), KeyCode::Char(' '))
+            .press(secs(90), KeyCode::Char('r'))
+            .press(secs(220), KeyCode::Char('q'));
+        let mut timer = Timer::new(mins(2), config.extend.clone())
+            .with_warnings(WarningCues::new(&config).unwrap())
+            .with_time(script.time());
+
+        let (screen, alerts) = run(&mut timer, script);
+
+        let warned = |frame: &Frame| frame.lines[1].color == Some(Color::Yellow);
+        assert!(!warned(&screen.frames()[0]));
+        assert!(screen.frames().iter().any(warned));
+        // The 1m warning, three countdown ticks and the end.
+        assert_eq!(
+            alerts.cues(),
+            [
+                Cue::Start,
+                Cue::Countdown,
+                Cue::Countdown,
+                Cue::Countdown,
+                Cue::Finished
+            ]
+        );
+    }
+
+    #[test]
+    fn repeats_after_alerting() {
+        let script = Script::default().press(secs(200), KeyCode::Char('q'));
+        let mut timer = Timer::new(mins(1), ExtendConfig::default())
+            .with_repeat(Repeat::Times(3))
+            .with_time(script.time());
+
+        let (screen, alerts) = run(&mut timer, script);
+
+        assert!(screen.showed("Timer\n0h 0m 59s"));
+        assert!(screen.showed("Iteration: 2/3\nTotal: 0h 1m 30s"));
+        let last = screen.last().unwrap().text();
+        assert!(last.contains("Timer has ended\n+0h 0m 19s"));
+        assert!(last.contains("Iteration: 3/3\nTotal: 0h 3m 19s"));
+        assert_eq!(alerts.played(), 3);
+    }
+}
+
+mod timers {
+    use porsmo::interval::Cue;
+    use porsmo::tui::timers::Timers;
+    use porsmo::tui::warning::WarningCues;
+
+    use super::*;
+
+    fn timers() -> Timers {
+        let timers = vec![(Some("tea".to_string()), mins(4)), (None, mins(1))];
+        Timers::new(timers, ExtendConfig::default())
+    }
+
+    #[test]
+    fn run_and_alert_independently() {
+        let script = Script::default()
+            .press(secs(30), KeyCode::Char(' '))
+            .press(secs(40), KeyCode::Char('a'))
+            .type_text(secs(40), "deploy check=2m")
+            .press(secs(40), KeyCode::Enter)
+            .press(secs(90), KeyCode::Up)
+            .press(secs(90), KeyCode::Up)
+            .press(secs(90), KeyCode::Char(' '))
+            .press(mins(6), KeyCode::Char('q'));
+        let mut timers = timers().with_time(script.time());
+
+        let (screen, alerts) = run(&mut timers, script);
+
+        assert!(screen.showed("New timer (name=time): deploy check=2m_"));
+        assert!(screen.showed("  Timer 2       +0h 0m 1s ended"));
+        let last = screen.last().unwrap().text();
+        assert!(last.contains("> tea           +0h 0m 59s ended"));
+        assert!(last.contains("  Timer 2       +0h 4m 59s ended"));
+        assert!(last.contains("  deploy check  +0h 3m 19s ended"));
+        assert_eq!(alerts.played(), 3);
+    }
+
+    #[test]
+    fn warn_each_on_its_own() {
+        let config = config("[warnings]\nat = [\"30s\"]");
+        let script = Script::default().press(mins(5), KeyCode::Char('q'));
+        let mut timers = timers()
+            .with_warnings(WarningCues::new(&config).unwrap())
+            .with_time(script.time());
+
+        let (_, alerts) = run(&mut timers, script);
+
+        // Timer 2's warning and end, then tea's.
+        assert_eq!(
+            alerts.cues(),
+            [Cue::Start, Cue::Finished, Cue::Start, Cue::Finished]
+        );
+    }
+
+    #[test]
+    fn scrolling_moves_the_selection() {
+        let script = Script::default()
+            .scroll(secs(1), false)
+            .scroll(secs(1), false)
+            .press(secs(2), KeyCode::Char('q'));
+        let mut timers = timers().with_time(script.time());
+
+        let (screen, _) = run(&mut timers, script);
+
+        assert!(screen.last().unwrap().text().contains("> Timer 2"));
+    }
+}
+
+mod interval {
+    use porsmo::interval::parse_plan;
+    use porsmo::tui::interval::IntervalApp;
+
+    use super::*;
+
+    #[test]
+    fn counts_rounds_and_cues() {
+        let mut plan = parse_plan("2x20s/10s").unwrap();
+        plan.warmup = secs(10);
+        let script = Script::default()
+            .press(secs(15), KeyCode::Char(' '))
+            .press(secs(45), KeyCode::Char(' '))
+            .press(mins(2), KeyCode::Char('q'));
+        let mut app = IntervalApp::new(plan).with_time(script.time());
+
+        let (screen, alerts) = run(&mut app, script);
+
+        assert!(screen.showed("Warm up"));
+        assert!(screen.showed("Work!\n0h 0m 15s"));
+        assert!(screen.showed("Round: 1/2\nTotal left: 0h 0m 45s"));
+        assert!(screen.showed("Rest"));
+        assert!(screen.showed("Round: 2/2"));
+        assert_eq!(screen.last().unwrap().lines[0].text, "Workout complete!");
+        // A countdown before every step ends, a start and an end per round,
+        // except that the finish replaces the last end.
+        assert_eq!(alerts.played(), 4 * 3 + 2 + 1 + 1);
+    }
+}
+
+mod routine {
+    use porsmo::interval::Cue;
+    use porsmo::tui::routine::RoutineApp;
+    use porsmo::tui::warning::WarningCues;
+
+    use super::*;
+
+    #[test]
+    fn runs_configured_phases() {
+        let config = config(
+            r#"
+            [routines.focus]
+            repeat = 2
+            phases = [
+                { name = "plan", duration = "5m", color = "yellow", auto_advance = true },
+                { name = "deep work", duration = "50m", sound = "high" },
+            ]
+            "#,
+        );
+        let routine = config.routines["focus"].clone();
+        let script = Script::default()
+            .press(mins(60), KeyCode::Enter)
+            .press(mins(120), KeyCode::Enter)
+            .press(mins(121), KeyCode::Char('q'));
+        let mut app = RoutineApp::new("focus".to_string(), routine)
+            .unwrap()
+            .with_time(script.time());
+
+        let (screen, alerts) = run(&mut app, script);
+
+        assert!(screen.showed("plan\n0h 4m 59s"));
+        assert!(screen.showed("deep work is over, next: plan\n+0h 4m 59s"));
+        assert!(screen.showed("Phase: 2/2\nRound: 2/2"));
+        assert!(screen.showed("deep work is over, that was the last phase"));
+        assert_eq!(
+            screen.last().unwrap().lines[0].text,
+            "Routine focus complete!"
+        );
+        assert_eq!(alerts.played(), 4);
+    }
+
+    #[test]
+    fn warns_in_every_round() {
+        let config = config(
+            r#"
+            [warnings]
+            at = ["1m"]
+
+            [routines.sprints]
+            repeat = 2
+            phases = [{ name = "sprint", duration = "5m", auto_advance = true }]
+            "#,
+        );
+        let routine = config.routines["sprints"].clone();
+        let script = Script::default().press(mins(11), KeyCode::Char('q'));
+        let mut app = RoutineApp::new("sprints".to_string(), routine)
+            .unwrap()
+            .with_warnings(WarningCues::new(&config).unwrap())
+            .with_time(script.time());
+
+        let (_, alerts) = run(&mut app, script);
+
+        assert_eq!(
+            alerts.cues(),
+            [Cue::Start, Cue::Finished, Cue::Start, Cue::Finished]
+        );
+    }
+}
+
+mod stopwatch {
+    use porsmo::tui::stopwatch::Stopwatch;
+
+    use super::*;
+
+    #[test]
+    fn pauses() {
+        let script = Script::default()
+            .press(secs(10), KeyCode::Char(' '))
+            .press(secs(20), KeyCode::Char(' '))
+            .press(secs(25), KeyCode::Char('q'));
+        let mut stopwatch = Stopwatch::default().with_time(script.time());
+
+        run(&mut stopwatch, script);
+
+        assert_eq!(stopwatch.elapsed().as_secs(), 15);
+    }
+
+    #[test]
+    fn script_without_quit_fails() {
+        let mut script = Script::default().press(secs(1), KeyCode::Char(' '));
+        let mut stopwatch = Stopwatch::default().with_time(script.time());
+
+        let result = run_with(
+            &mut stopwatch,
+            &mut MemoryScreen::default(),
+            &mut script,
+            &mut Alerts::silent(),
+        );
+
+        assert!(result.is_err());
+    }
+}
+
+mod layout {
+    use porsmo::tui::layout::{button_at, choose, lay_out, truncate, Layout};
+    use porsmo::tui::stopwatch::Stopwatch;
+    use porsmo::tui::timer::Timer;
+    use porsmo::tui::ui::Line;
+
+    use super::*;
+
+    #[test]
+    fn centers_or_compacts() {
+        let lines: Vec<Line> = vec![
+            "Pomodoro (Work)".into(),
+            "0h 24m 59s".into(),
+            "[Q]: Quit, [S]: Skip, [Space]: Pause/Resume, [R]: Reset".into(),
+        ];
+
+        assert_eq!(choose(&lines, 80, 24), Layout::Expanded);
+        let placed = lay_out(&lines, 80, 24);
+        assert_eq!((placed[0].x, placed[0].y), (32, 10));
+        assert_eq!((placed[1].x, placed[1].y), (35, 11));
+
+        // The controls wrap between their parts.
+        let placed = lay_out(&lines, 40, 24);
+        let rows: Vec<&str> = placed.iter().map(|p| p.line.text.as_str()).collect();
+        assert_eq!(
+            rows,
+            [
+                "Pomodoro (Work)",
+                "0h 24m 59s",
+                "[Q]: Quit, [S]: Skip",
+                "[Space]: Pause/Resume, [R]: Reset",
+            ]
+        );
+
+        assert_eq!(choose(&lines, 20, 2), Layout::Compact);
+        let placed = lay_out(&lines, 20, 2);
+        assert_eq!(placed.len(), 2);
+        assert_eq!((placed[1].x, placed[1].y), (0, 1));
+        assert_eq!(truncate("🍅 24:59 Work #1", 8), "🍅 24:5…");
+    }
+
+    #[test]
+    fn resize_reaches_the_screen() {
+        let script = Script::default()
+            .event(secs(1), crossterm::event::Event::Resize(30, 5))
+            .press(secs(1), KeyCode::Char('q'));
+        let mut stopwatch = Stopwatch::default().with_time(script.time());
+
+        let (screen, _) = run(&mut stopwatch, script);
+
+        assert_eq!(screen.size(), Some((30, 5)));
+    }
+
+    #[test]
+    fn clicking_controls_presses_their_keys() {
+        // "[Q]: quit, [Space]: pause/resume, [R]: Reset, [+/>]: +1m 0s/+5m 0s"
+        // is centered on the 13th row, so "[Space]" starts at column 18 and
+        // "+5m 0s" at 68.
+        let script = Script::default()
+            .click(secs(10), 18, 12)
+            .click(secs(15), 2, 2)
+            .click(secs(20), 68, 12)
+            .press(secs(30), KeyCode::Char('q'));
+        let mut timer = Timer::new(mins(1), ExtendConfig::default()).with_time(script.time());
+
+        let (screen, _) = run(&mut timer, script);
+
+        // Paused with 50s left, then extended.
+        assert_eq!(screen.last().unwrap().lines[1].text, "0h 5m 50s");
+    }
+
+    #[test]
+    fn buttons_are_found_by_position() {
+        let lines: Vec<Line> = vec!["[Q]: Quit, [Enter/Y]: Yes/Sure, [Esc/N]: No".into()];
+        let placed = lay_out(&lines, 20, 1);
+        assert_eq!(button_at(&placed, 0, 0), Some(KeyCode::Char('q')));
+        // Cut off, so no longer a button.
+        assert_eq!(placed[0].line.text, "[Q]: Quit, [Enter/Y…");
+        assert_eq!(button_at(&placed, 11, 0), None);
+
+        let placed = lay_out(&lines, 80, 1);
+        let x = placed[0].x;
+        assert_eq!(button_at(&placed, x + 22, 0), Some(KeyCode::Enter));
+        assert_eq!(button_at(&placed, x + 26, 0), Some(KeyCode::Char('y')));
+        assert_eq!(button_at(&placed, x + 9, 0), None);
+        assert_eq!(button_at(&placed, x + 33, 0), Some(KeyCode::Esc));
+        assert_eq!(button_at(&placed, x + 50, 0), None);
+    }
+}